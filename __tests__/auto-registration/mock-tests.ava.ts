@@ -0,0 +1,166 @@
+import anyTest, { TestFn } from "ava";
+import { BN, NEAR, NearAccount, TransactionResult, Worker } from "near-workspaces";
+import { daoBotConfig, LARGE_GAS } from "../utils/general";
+
+// Runs the human_only flow against the mock DAO and SBT registry, with a plain account standing in for Keypom
+const test = anyTest as TestFn<{
+    worker: Worker;
+    accounts: Record<string, NearAccount>;
+}>;
+
+const PROPOSAL_BOND = NEAR.parse("0.1").toString();
+const CLAIM_DEPOSIT = NEAR.parse("0.2").toString();
+const DROP_ID = "1";
+
+function policy(council: string, daoBot: string) {
+    const group = (name: string, accounts: string[]) => ({
+        name,
+        kind: { Group: accounts },
+        permissions: ["*:*"],
+        vote_policy: {},
+    });
+    return {
+        roles: [group("council", [council]), group("keypom-daobot", [daoBot]), group("member", [])],
+        default_vote_policy: { weight_kind: "RoleWeight", quorum: "0", threshold: [1, 2] },
+        proposal_bond: PROPOSAL_BOND,
+        proposal_period: "604800000000000",
+        bounty_bond: "1000000000000000000000000",
+        bounty_forgiveness_period: "86400000000000",
+    };
+}
+
+// Relay a claim to the bot the way Keypom does, injecting the funder, drop id and claiming account
+async function claim(keypom: NearAccount, daoBot: NearAccount, dao: NearAccount, funder: NearAccount, member: NearAccount): Promise<TransactionResult> {
+    return keypom.callRaw(daoBot, 'new_auto_registration', {
+        dao_contract: dao.accountId,
+        keypom_args: {
+            account_id_field: "proposal.kind.AddMemberToRole.member_id",
+            drop_id_field: "drop_id",
+            key_id_field: null,
+            funder_id_field: "funder",
+        },
+        funder: funder.accountId,
+        drop_id: DROP_ID,
+        proposal: {
+            description: "mock onboarding",
+            kind: { AddMemberToRole: { member_id: member.accountId, role: "member" } },
+        },
+    }, { gas: LARGE_GAS, attachedDeposit: CLAIM_DEPOSIT });
+}
+
+function events(result: TransactionResult, name: string): any[] {
+    return result.logs
+        .filter((log) => log.startsWith("EVENT_JSON:"))
+        .map((log) => JSON.parse(log.slice("EVENT_JSON:".length)))
+        .filter((event) => event.event === name)
+        .flatMap((event) => event.data);
+}
+
+async function giveHumanToken(registry: NearAccount, member: NearAccount) {
+    await registry.call(registry, 'add_token', {
+        owner: member.accountId,
+        issuer: "fractal.test.near",
+        token: { token: 1, metadata: { class: 1, issued_at: null, expires_at: null, reference: null, reference_hash: null } },
+    });
+}
+
+test.beforeEach(async (t) => {
+    const worker = await Worker.init();
+    const root = worker.rootAccount;
+
+    const funder = await root.createSubAccount('funder');
+    const member = await root.createSubAccount('member');
+    const keypom = await root.createSubAccount('keypom');
+    const owner = await root.createSubAccount('owner');
+
+    const dao = await root.devDeploy(`./testing-wasms/mock_dao.wasm`);
+    const registry = await root.devDeploy(`./testing-wasms/mock_sbt_registry.wasm`);
+    const daoBot = await root.devDeploy(`./testing-wasms/dao_bot.wasm`);
+
+    await daoBot.call(daoBot, 'new', { owner_id: owner.accountId, config: daoBotConfig(keypom.accountId, { sbt_registry: registry.accountId }) });
+    await dao.call(dao, 'set_policy', { policy: policy(funder.accountId, daoBot.accountId) });
+
+    await funder.call(daoBot, 'register_drop_config', {
+        drop_id: DROP_ID,
+        config: { dao_contract: dao.accountId, allowed_roles: ['member'], human_only: true, human_requirement: null, max_registrations: null, expires_at: null }
+    }, { attachedDeposit: NEAR.parse('0.1').toString() });
+
+    t.context.worker = worker;
+    t.context.accounts = { root, funder, member, keypom, owner, dao, registry, daoBot };
+});
+
+test.afterEach(async t => {
+    await t.context.worker.tearDown().catch(error => {
+        console.log('Failed to tear down the worker:', error);
+    });
+});
+
+// Asserts the registration failed with `reason` and its refund reached the funder
+async function assertRefundedFailure(t: any, result: TransactionResult, reason: string, balanceBefore: BN) {
+    const { funder, daoBot } = t.context.accounts;
+
+    const registration: any = await daoBot.view('get_registration', { registration_id: 0 });
+    t.is(registration.status, "Failed");
+    t.is(registration.failure_reason, reason);
+
+    const [failure] = events(result, "registration_failed");
+    t.is(failure.reason, reason);
+    t.is(failure.refund_receiver, funder.accountId);
+    t.true(new BN(failure.refund).gt(new BN(PROPOSAL_BOND)));
+
+    const balanceAfter = (await funder.balance()).total;
+    t.is(balanceAfter.sub(balanceBefore).toString(), failure.refund);
+}
+
+test('Human claimer is onboarded and the bond is credited back', async t => {
+    const { funder, member, keypom, dao, registry, daoBot } = t.context.accounts;
+    await giveHumanToken(registry, member);
+    const balanceBefore = (await funder.balance()).total;
+
+    const result = await claim(keypom, daoBot, dao, funder, member);
+
+    const registration: any = await daoBot.view('get_registration', { registration_id: 0 });
+    t.is(registration.status, "ProposalApproved");
+    t.is(registration.proposal_id, 0);
+    t.is(events(result, "human_check_passed").length, 1);
+    t.is(events(result, "registration_failed").length, 0);
+
+    // What the bond and storage didn't use is refunded, the bond is credited once the DAO returns it
+    const refunded = (await funder.balance()).total.sub(balanceBefore);
+    t.true(refunded.gtn(0));
+    t.true(refunded.lt(new BN(CLAIM_DEPOSIT).sub(new BN(PROPOSAL_BOND))));
+    t.is(await daoBot.view('view_balance', { account_id: funder.accountId }), PROPOSAL_BOND);
+});
+
+test('Claimer without humanity proof is refused and the deposit refunded', async t => {
+    const { funder, member, keypom, dao, daoBot } = t.context.accounts;
+    const balanceBefore = (await funder.balance()).total;
+
+    const result = await claim(keypom, daoBot, dao, funder, member);
+
+    t.is(events(result, "human_check_failed").length, 1);
+    await assertRefundedFailure(t, result, "CLAIMING ACCOUNT MUST BE HUMAN", balanceBefore);
+});
+
+test('Registry failure fails the registration and refunds the deposit', async t => {
+    const { funder, member, keypom, dao, registry, daoBot } = t.context.accounts;
+    await giveHumanToken(registry, member);
+    await registry.call(registry, 'set_response', { method: "is_human", response: "Fail" });
+    const balanceBefore = (await funder.balance()).total;
+
+    const result = await claim(keypom, daoBot, dao, funder, member);
+
+    await assertRefundedFailure(t, result, "PROBLEM WITH PROMISE", balanceBefore);
+});
+
+test('Malformed DAO policy fails the registration and refunds the deposit', async t => {
+    const { funder, member, keypom, dao, registry, daoBot } = t.context.accounts;
+    await giveHumanToken(registry, member);
+    await dao.call(dao, 'set_response', { method: "get_policy", response: "Malformed" });
+    const balanceBefore = (await funder.balance()).total;
+
+    const result = await claim(keypom, daoBot, dao, funder, member);
+
+    t.is(events(result, "human_check_passed").length, 1);
+    await assertRefundedFailure(t, result, "ERR_WRONG_VAL_RECEIVED", balanceBefore);
+});
@@ -12,6 +12,19 @@ export const CONTRACT_METADATA = {
   "link": "https://github.com/mattlockyer/proxy/commit/71a943ea8b7f5a3b7d9e9ac2208940f074f8afba",
 }
 
+// Config the bot is initialized with, sandbox builds have no network defaults
+export function daoBotConfig(keypomContract: string, overrides: Record<string, unknown> = {}) {
+  return {
+    keypom_contract: keypomContract,
+    sbt_registry: "registry.test.near",
+    council_role: "council",
+    view_call_gas: "0",
+    proposal_call_gas: "0",
+    refund_receiver: "Funder",
+    ...overrides,
+  }
+}
+
 export function displayFailureLog(
   transaction: TransactionResult
 ) {
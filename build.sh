@@ -1,7 +1,28 @@
 #!/bin/sh
+set -e
 
 echo ">> Building contract"
 
 rustup target add wasm32-unknown-unknown
-cargo build --all --target wasm32-unknown-unknown --release
-cp ./target/wasm32-unknown-unknown/release/dao_bot.wasm ./out/
+
+# Reported by contract_source_metadata
+export DAO_BOT_COMMIT=$(git rev-parse HEAD)
+
+mkdir -p out testing-wasms
+
+# build <features> <destination>
+build() {
+    cargo build --target wasm32-unknown-unknown --release --features "$1"
+    cp ./target/wasm32-unknown-unknown/release/dao_bot.wasm "$2"
+}
+
+build "mainnet" ./out/dao_bot_mainnet.wasm
+build "testnet" ./out/dao_bot_testnet.wasm
+build "" ./testing-wasms/dao_bot.wasm
+build "v1" ./testing-wasms/dao_bot_v1.wasm
+
+echo ">> Building mocks"
+
+cargo build -p mock-dao -p mock-sbt-registry --target wasm32-unknown-unknown --release
+cp ./target/wasm32-unknown-unknown/release/mock_dao.wasm ./testing-wasms/
+cp ./target/wasm32-unknown-unknown/release/mock_sbt_registry.wasm ./testing-wasms/
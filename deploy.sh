@@ -1,5 +1,10 @@
 #!/bin/sh
 
+if [ -z "$OWNER" ]; then
+  echo ">> Set OWNER to the account that will own the bot"
+  exit 1
+fi
+
 ./build.sh
 
 if [ $? -ne 0 ]; then
@@ -10,4 +15,5 @@ fi
 echo ">> Deploying contract"
 
 # https://docs.near.org/tools/near-cli#near-dev-deploy
-near dev-deploy --wasmFile ./target/wasm32-unknown-unknown/release/hello_near.wasm
+# Initialize in the same transaction so nobody else can call `new` first
+near dev-deploy --wasmFile ./out/dao_bot_testnet.wasm --initFunction new --initArgs "{\"owner_id\": \"$OWNER\"}"
@@ -0,0 +1,226 @@
+//! Minimal sputnikdao2 stand-in implementing the bot's `ExtDao` interface, for sandbox tests.
+//! Every method can be made to fail or return a malformed payload with `set_response`.
+//!
+//! Like sputnikdao2, `add_proposal` takes the policy's `proposal_bond` and `act_proposal` sends it
+//! back to the proposer once the proposal is approved, rejected or expired.
+
+use dao_bot_types::ext_traits::ExtDao;
+use dao_bot_types::{Action, Policy, ProposalInput, ProposalOutput, ProposalStatus};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::serde_json::{json, Value};
+use near_sdk::{env, near_bindgen, require, AccountId, Balance, Promise};
+use std::collections::HashMap;
+
+/// How a mocked method responds.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum MockResponse {
+    /// Behave like the real contract.
+    Ok,
+    /// Panic, failing the caller's promise.
+    Fail,
+    /// Succeed with a payload the caller can't parse.
+    Malformed,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct MockProposal {
+    pub proposer: AccountId,
+    pub description: String,
+    pub status: ProposalStatus,
+    /// Bond taken from the proposer, returned when the proposal gets to a status that returns it.
+    pub bond: Balance,
+}
+
+#[near_bindgen]
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct MockDao {
+    policy: Option<Policy>,
+    proposals: Vec<MockProposal>,
+    /// Status a proposal moves to when it gets a `VoteApprove`.
+    status_after_vote: ProposalStatus,
+    /// Response of each method by name, `Ok` if unset.
+    responses: HashMap<String, MockResponse>,
+}
+
+impl Default for MockDao {
+    fn default() -> Self {
+        Self {
+            policy: None,
+            proposals: vec![],
+            status_after_vote: ProposalStatus::Approved,
+            responses: HashMap::new(),
+        }
+    }
+}
+
+impl ExtDao for MockDao {
+    fn get_policy(&self) -> Policy {
+        self.policy.clone().unwrap_or_else(|| env::panic_str("POLICY NOT SET"))
+    }
+
+    fn act_proposal(&mut self, id: u64, action: Action, _memo: Option<String>) {
+        let proposal = self.proposals.get_mut(id as usize).unwrap_or_else(|| env::panic_str("PROPOSAL NOT FOUND"));
+        let returned_bond = proposal.status.returns_bond();
+        match action {
+            Action::VoteApprove => proposal.status = self.status_after_vote,
+            Action::VoteReject => proposal.status = ProposalStatus::Rejected,
+            Action::VoteRemove => proposal.status = ProposalStatus::Removed,
+            Action::Finalize => proposal.status = ProposalStatus::Expired,
+            Action::AddProposal | Action::RemoveProposal => env::panic_str("UNSUPPORTED ACTION"),
+        }
+        if proposal.status.returns_bond() && !returned_bond && proposal.bond > 0 {
+            Promise::new(proposal.proposer.clone()).transfer(proposal.bond);
+        }
+    }
+
+    fn add_proposal(&mut self, proposal: ProposalInput) {
+        let bond = self.policy.as_ref().map_or(0, |policy| policy.proposal_bond.0);
+        require!(env::attached_deposit() >= bond, "ERR_MIN_BOND");
+        self.proposals.push(MockProposal {
+            proposer: env::predecessor_account_id(),
+            description: proposal.description,
+            status: ProposalStatus::InProgress,
+            bond,
+        });
+    }
+
+    fn get_proposal(&self, id: u64) -> ProposalOutput {
+        let proposal = self.proposals.get(id as usize).unwrap_or_else(|| env::panic_str("PROPOSAL NOT FOUND"));
+        ProposalOutput { id, proposer: proposal.proposer.clone(), status: proposal.status }
+    }
+}
+
+#[near_bindgen]
+impl MockDao {
+    pub fn set_policy(&mut self, policy: Policy) {
+        self.policy = Some(policy);
+    }
+
+    pub fn set_status_after_vote(&mut self, status: ProposalStatus) {
+        self.status_after_vote = status;
+    }
+
+    pub fn set_response(&mut self, method: String, response: MockResponse) {
+        self.responses.insert(method, response);
+    }
+
+    pub fn get_policy(&self) -> Value {
+        respond(self.response("get_policy"), "get_policy", || json!(ExtDao::get_policy(self)))
+    }
+
+    pub fn act_proposal(&mut self, id: u64, action: Action, memo: Option<String>) -> Value {
+        respond(self.response("act_proposal"), "act_proposal", || {
+            ExtDao::act_proposal(self, id, action, memo);
+            Value::Null
+        })
+    }
+
+    /// Returns the id of the new proposal, like sputnikdao2.
+    #[payable]
+    pub fn add_proposal(&mut self, proposal: ProposalInput) -> Value {
+        respond(self.response("add_proposal"), "add_proposal", || {
+            ExtDao::add_proposal(self, proposal);
+            json!(self.proposals.len() - 1)
+        })
+    }
+
+    pub fn get_proposal(&self, id: u64) -> Value {
+        respond(self.response("get_proposal"), "get_proposal", || json!(ExtDao::get_proposal(self, id)))
+    }
+}
+
+impl MockDao {
+    fn response(&self, method: &str) -> MockResponse {
+        self.responses.get(method).copied().unwrap_or(MockResponse::Ok)
+    }
+}
+
+/// Apply `response` to a call of `method`, `ok` builds the real result.
+fn respond(response: MockResponse, method: &str, ok: impl FnOnce() -> Value) -> Value {
+    match response {
+        MockResponse::Ok => ok(),
+        MockResponse::Fail => env::panic_str(&format!("MOCK {} FAILED", method.to_uppercase())),
+        MockResponse::Malformed => json!({ "malformed": true }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dao_bot_types::{ProposalKind, VotePolicy, WeightKind, WeightOrRatio};
+    use near_sdk::json_types::{U128, U64};
+    use near_sdk::test_utils::{get_created_receipts, VMContextBuilder};
+    use near_sdk::mock::VmAction;
+    use near_sdk::testing_env;
+
+    fn policy(proposal_bond: Balance) -> Policy {
+        Policy {
+            roles: vec![],
+            default_vote_policy: VotePolicy { weight_kind: WeightKind::RoleWeight, quorum: U128(0), threshold: WeightOrRatio::Ratio(1, 2) },
+            proposal_bond: U128(proposal_bond),
+            proposal_period: U64(0),
+            bounty_bond: U128(0),
+            bounty_forgiveness_period: U64(0),
+        }
+    }
+
+    fn proposal() -> ProposalInput {
+        ProposalInput {
+            description: "Auto-registration".to_string(),
+            kind: ProposalKind::AddMemberToRole { member_id: "member.near".parse().unwrap(), role: "member".to_string() },
+        }
+    }
+
+    #[test]
+    fn votes_move_proposals_to_configured_status() {
+        testing_env!(VMContextBuilder::new().predecessor_account_id("bot.near".parse().unwrap()).build());
+        let mut dao = MockDao::default();
+        dao.set_status_after_vote(ProposalStatus::InProgress);
+
+        assert_eq!(dao.add_proposal(proposal()), json!(0));
+        dao.act_proposal(0, Action::VoteApprove, None);
+        assert_eq!(dao.get_proposal(0), json!({ "id": 0, "proposer": "bot.near", "status": "InProgress" }));
+    }
+
+    #[test]
+    fn approval_returns_the_bond_to_the_proposer() {
+        let bond = 100_000_000_000_000_000_000_000;
+        testing_env!(VMContextBuilder::new().predecessor_account_id("bot.near".parse().unwrap()).attached_deposit(bond).build());
+        let mut dao = MockDao::default();
+        dao.set_policy(policy(bond));
+
+        dao.add_proposal(proposal());
+        assert_eq!(dao.proposals[0].bond, bond);
+        assert!(get_created_receipts().is_empty());
+
+        dao.act_proposal(0, Action::VoteApprove, None);
+        let receipts = get_created_receipts();
+        assert_eq!(receipts.len(), 1);
+        assert_eq!(receipts[0].receiver_id, "bot.near".parse::<AccountId>().unwrap());
+        assert!(matches!(receipts[0].actions[..], [VmAction::Transfer { deposit }] if deposit == bond));
+    }
+
+    #[test]
+    fn removal_keeps_the_bond() {
+        let bond = 100_000_000_000_000_000_000_000;
+        testing_env!(VMContextBuilder::new().predecessor_account_id("bot.near".parse().unwrap()).attached_deposit(bond).build());
+        let mut dao = MockDao::default();
+        dao.set_policy(policy(bond));
+
+        dao.add_proposal(proposal());
+        dao.act_proposal(0, Action::VoteRemove, None);
+        assert!(get_created_receipts().is_empty());
+    }
+
+    #[test]
+    fn malformed_response_skips_the_call() {
+        testing_env!(VMContextBuilder::new().build());
+        let mut dao = MockDao::default();
+        dao.set_response("add_proposal".to_string(), MockResponse::Malformed);
+
+        assert_eq!(dao.add_proposal(proposal()), json!({ "malformed": true }));
+        assert!(dao.proposals.is_empty());
+    }
+}
@@ -0,0 +1,152 @@
+//! Minimal i-am-human registry stand-in implementing the bot's `ExtSBTRegistry` interface,
+//! for sandbox tests of the `human_only` path. Every method can be made to fail or return a
+//! malformed payload with `set_response`.
+
+use dao_bot_types::ext_traits::ExtSBTRegistry;
+use dao_bot_types::{ClassId, OwnedToken};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::serde_json::{json, Value};
+use near_sdk::{env, near_bindgen, AccountId};
+use std::collections::HashMap;
+
+/// How a mocked method responds.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum MockResponse {
+    /// Behave like the real contract.
+    Ok,
+    /// Panic, failing the caller's promise.
+    Fail,
+    /// Succeed with a payload the caller can't parse.
+    Malformed,
+}
+
+#[near_bindgen]
+#[derive(BorshSerialize, BorshDeserialize, Default)]
+pub struct MockSbtRegistry {
+    /// Tokens of each owner, grouped by issuer.
+    tokens: HashMap<AccountId, Vec<(AccountId, Vec<OwnedToken>)>>,
+    /// Response of each method by name, `Ok` if unset.
+    responses: HashMap<String, MockResponse>,
+}
+
+impl ExtSBTRegistry for MockSbtRegistry {
+    /// Classes of the valid tokens `account` holds, for every issuer.
+    fn is_human(&self, account: AccountId) -> Vec<(AccountId, Vec<ClassId>)> {
+        ExtSBTRegistry::sbt_tokens_by_owner(self, account, None, None, None, Some(false))
+            .into_iter()
+            .map(|(issuer, tokens)| (issuer, tokens.iter().map(|token| token.metadata.class).collect()))
+            .collect()
+    }
+
+    fn sbt_tokens_by_owner(&self, account: AccountId, issuer: Option<AccountId>, from_class: Option<u64>, limit: Option<u32>, with_expired: Option<bool>) -> Vec<(AccountId, Vec<OwnedToken>)> {
+        let now = env::block_timestamp_ms();
+        let with_expired = with_expired.unwrap_or(false);
+        self.tokens.get(&account)
+            .into_iter()
+            .flatten()
+            .filter(|(token_issuer, _)| issuer.as_ref().is_none_or(|issuer| issuer == token_issuer))
+            .map(|(token_issuer, tokens)| {
+                let tokens = tokens.iter()
+                    .filter(|token| from_class.is_none_or(|class| token.metadata.class >= class))
+                    .filter(|token| with_expired || token.metadata.expires_at.is_none_or(|expires_at| expires_at > now))
+                    .take(limit.map_or(usize::MAX, |limit| limit as usize))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                (token_issuer.clone(), tokens)
+            })
+            .filter(|(_, tokens)| !tokens.is_empty())
+            .collect()
+    }
+}
+
+#[near_bindgen]
+impl MockSbtRegistry {
+    /// Give `owner` a token from `issuer`.
+    pub fn add_token(&mut self, owner: AccountId, issuer: AccountId, token: OwnedToken) {
+        let issuers = self.tokens.entry(owner).or_default();
+        match issuers.iter_mut().find(|(i, _)| *i == issuer) {
+            Some((_, tokens)) => tokens.push(token),
+            None => issuers.push((issuer, vec![token])),
+        }
+    }
+
+    pub fn set_response(&mut self, method: String, response: MockResponse) {
+        self.responses.insert(method, response);
+    }
+
+    pub fn is_human(&self, account: AccountId) -> Value {
+        respond(self.response("is_human"), "is_human", || json!(ExtSBTRegistry::is_human(self, account)))
+    }
+
+    pub fn sbt_tokens_by_owner(&self, account: AccountId, issuer: Option<AccountId>, from_class: Option<u64>, limit: Option<u32>, with_expired: Option<bool>) -> Value {
+        respond(self.response("sbt_tokens_by_owner"), "sbt_tokens_by_owner", || {
+            json!(ExtSBTRegistry::sbt_tokens_by_owner(self, account, issuer, from_class, limit, with_expired))
+        })
+    }
+}
+
+impl MockSbtRegistry {
+    fn response(&self, method: &str) -> MockResponse {
+        self.responses.get(method).copied().unwrap_or(MockResponse::Ok)
+    }
+}
+
+/// Apply `response` to a call of `method`, `ok` builds the real result.
+fn respond(response: MockResponse, method: &str, ok: impl FnOnce() -> Value) -> Value {
+    match response {
+        MockResponse::Ok => ok(),
+        MockResponse::Fail => env::panic_str(&format!("MOCK {} FAILED", method.to_uppercase())),
+        MockResponse::Malformed => json!({ "malformed": true }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dao_bot_types::TokenMetadata;
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::testing_env;
+
+    const NOW_MS: u64 = 1_700_000_000_000;
+
+    fn token(token: u64, class: ClassId, expires_at: Option<u64>) -> OwnedToken {
+        OwnedToken {
+            token,
+            metadata: TokenMetadata { class, issued_at: Some(NOW_MS), expires_at, reference: None, reference_hash: None },
+        }
+    }
+
+    fn registry() -> MockSbtRegistry {
+        testing_env!(VMContextBuilder::new().block_timestamp(NOW_MS * 1_000_000).build());
+        let mut registry = MockSbtRegistry::default();
+        let owner: AccountId = "member.near".parse().unwrap();
+        registry.add_token(owner.clone(), "fractal.near".parse().unwrap(), token(1, 1, None));
+        registry.add_token(owner.clone(), "fractal.near".parse().unwrap(), token(2, 2, Some(NOW_MS - 1)));
+        registry.add_token(owner, "og.near".parse().unwrap(), token(3, 1, None));
+        registry
+    }
+
+    #[test]
+    fn tokens_are_filtered_by_issuer_and_expiry() {
+        let registry = registry();
+        let owner: AccountId = "member.near".parse().unwrap();
+
+        let tokens = ExtSBTRegistry::sbt_tokens_by_owner(&registry, owner.clone(), Some("fractal.near".parse().unwrap()), None, None, None);
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].1.iter().map(|t| t.token).collect::<Vec<_>>(), vec![1]);
+
+        let tokens = ExtSBTRegistry::sbt_tokens_by_owner(&registry, owner.clone(), Some("fractal.near".parse().unwrap()), None, None, Some(true));
+        assert_eq!(tokens[0].1.len(), 2);
+
+        assert_eq!(registry.is_human(owner), json!([["fractal.near", [1]], ["og.near", [1]]]));
+    }
+
+    #[test]
+    fn malformed_response_is_unparseable() {
+        let mut registry = registry();
+        registry.set_response("is_human".to_string(), MockResponse::Malformed);
+        assert_eq!(registry.is_human("member.near".parse().unwrap()), json!({ "malformed": true }));
+    }
+}
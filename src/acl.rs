@@ -0,0 +1,92 @@
+use crate::*;
+
+/// Delegated admin roles. The owner implicitly holds all of them.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdminRole {
+    /// Can change the contract configuration.
+    ConfigAdmin,
+    /// Can pause and unpause registrations.
+    Pauser,
+    /// Can move funds that are not owned by any funder.
+    Treasurer,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Start a two-step ownership transfer, `new_owner` must call `accept_ownership` to complete it.
+    #[payable]
+    pub fn transfer_ownership(&mut self, new_owner: AccountId) {
+        assert_one_yocto();
+        self.assert_owner();
+        log!("Ownership transfer to {} proposed", new_owner);
+        self.pending_owner = Some(new_owner);
+    }
+
+    /// Complete an ownership transfer started by `transfer_ownership`.
+    #[payable]
+    pub fn accept_ownership(&mut self) {
+        assert_one_yocto();
+        let caller = env::predecessor_account_id();
+        require!(self.pending_owner.as_ref() == Some(&caller), "ONLY PENDING OWNER CAN ACCEPT OWNERSHIP");
+        log!("Ownership transferred from {} to {}", self.owner_id, caller);
+        self.owner_id = caller;
+        self.pending_owner = None;
+    }
+
+    #[payable]
+    pub fn grant_role(&mut self, account_id: AccountId, role: AdminRole) {
+        assert_one_yocto();
+        self.assert_owner();
+        let mut roles = self.admins.get(&account_id).unwrap_or_default();
+        if !roles.contains(&role) {
+            roles.push(role);
+            self.admins.insert(&account_id, &roles);
+        }
+    }
+
+    #[payable]
+    pub fn revoke_role(&mut self, account_id: AccountId, role: AdminRole) {
+        assert_one_yocto();
+        self.assert_owner();
+        if let Some(mut roles) = self.admins.get(&account_id) {
+            roles.retain(|r| *r != role);
+            if roles.is_empty() {
+                self.admins.remove(&account_id);
+            } else {
+                self.admins.insert(&account_id, &roles);
+            }
+        }
+    }
+
+    pub fn view_owner(&self) -> AccountId {
+        self.owner_id.clone()
+    }
+
+    pub fn view_pending_owner(&self) -> Option<AccountId> {
+        self.pending_owner.clone()
+    }
+
+    /// Roles explicitly granted to `account_id`, does not include the owner's implicit roles.
+    pub fn view_roles(&self, account_id: AccountId) -> Vec<AdminRole> {
+        self.admins.get(&account_id).unwrap_or_default()
+    }
+
+    pub fn view_admins(&self) -> Vec<(AccountId, Vec<AdminRole>)> {
+        self.admins.to_vec()
+    }
+}
+
+impl Contract {
+    pub(crate) fn assert_owner(&self) {
+        require!(env::predecessor_account_id() == self.owner_id, "ONLY OWNER CAN CALL THIS METHOD");
+    }
+
+    pub(crate) fn has_role(&self, account_id: &AccountId, role: AdminRole) -> bool {
+        *account_id == self.owner_id
+            || self.admins.get(account_id).is_some_and(|roles| roles.contains(&role))
+    }
+
+    pub(crate) fn assert_role(&self, role: AdminRole) {
+        require!(self.has_role(&env::predecessor_account_id(), role), format!("MISSING ADMIN ROLE {:?}", role));
+    }
+}
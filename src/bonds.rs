@@ -0,0 +1,84 @@
+use crate::*;
+
+/// Proposal bond posted by the bot on behalf of a funder.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone)]
+pub struct BondRecord {
+    /// Funder whose drop paid the bond.
+    pub funder: AccountId,
+    /// Amount attached to `add_proposal`.
+    pub amount: U128,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Check the status of a proposal the bot added and, once the DAO has returned its bond,
+    /// credit the bond to the funder who paid it. Can be called by anyone.
+    pub fn claim_bond(&mut self, dao_contract: AccountId, proposal_id: u64) -> Promise {
+        require!(self.bonds.contains_key(&(dao_contract.clone(), proposal_id)), "NO BOND RECORDED FOR THIS PROPOSAL");
+
+        ext_dao::ext(dao_contract.clone())
+        .with_static_gas(self.config.view_call_gas)
+        .get_proposal(proposal_id)
+        .then(
+            Self::ext(env::current_account_id())
+            .on_claim_bond(dao_contract, proposal_id)
+        )
+    }
+
+    #[private]
+    pub fn on_claim_bond(&mut self, dao_contract: AccountId, proposal_id: u64) -> Option<ProposalStatus> {
+        let proposal = match env::promise_result(0) {
+            PromiseResult::Successful(val) => near_sdk::serde_json::from_slice::<ProposalOutput>(&val).ok()?,
+            _ => return None,
+        };
+        self.internal_settle_bond(&dao_contract, proposal_id, proposal.status);
+        Some(proposal.status)
+    }
+
+    pub fn view_bond(&self, dao_contract: AccountId, proposal_id: u64) -> Option<BondRecord> {
+        self.bonds.get(&(dao_contract, proposal_id))
+    }
+}
+
+impl Contract {
+    /// Credit a recorded bond back to its funder if `status` means the DAO returned it,
+    /// or forget it if the DAO kept it.
+    pub(crate) fn internal_settle_bond(&mut self, dao_contract: &AccountId, proposal_id: u64, status: ProposalStatus) {
+        let key = (dao_contract.clone(), proposal_id);
+        let record = match self.bonds.get(&key) {
+            Some(record) => record,
+            None => return,
+        };
+
+        if status.returns_bond() {
+            self.bonds.remove(&key);
+            let balance = self.balances.get(&record.funder).unwrap_or(0);
+            self.internal_set_balance(&record.funder, balance + record.amount.0);
+            log!("Bond of {} for proposal {} on {} credited to {}", record.amount.0, proposal_id, dao_contract, record.funder);
+        } else if status == ProposalStatus::Removed {
+            self.bonds.remove(&key);
+            log!("Bond for proposal {} on {} was kept by the DAO", proposal_id, dao_contract);
+        }
+    }
+
+    /// Top up an insufficient attached deposit from the funder's credited balance.
+    /// Returns the total deposit available for the registration, or None if it can't cover `required`.
+    /// The treasury, funder of legacy claims, never tops up a deposit.
+    pub(crate) fn internal_rollover_deposit(&mut self, funder: &AccountId, attached: Balance, required: Balance) -> Option<Balance> {
+        if attached >= required {
+            return Some(attached);
+        }
+        if *funder == env::current_account_id() {
+            return None;
+        }
+        let shortfall = required - attached;
+        let balance = self.balances.get(funder).unwrap_or(0);
+        if balance < shortfall {
+            return None;
+        }
+
+        self.internal_set_balance(funder, balance - shortfall);
+        log!("Rolled over {} from {}'s balance", shortfall, funder);
+        Some(required)
+    }
+}
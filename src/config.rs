@@ -0,0 +1,74 @@
+use crate::*;
+
+#[cfg(all(feature = "mainnet", feature = "testnet"))]
+compile_error!("the `mainnet` and `testnet` features are mutually exclusive");
+
+/// Deployment specific configuration, passed to `new` so that the same wasm
+/// can be used on mainnet, testnet and sandbox.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone)]
+pub struct ContractConfig {
+    /// Keypom contract allowed to call `new_auto_registration`.
+    pub keypom_contract: AccountId,
+    /// SBT registry used for Proof-of-Humanity checks, i.e `registry.i-am-human.near`.
+    pub sbt_registry: AccountId,
+    /// Name of the DAO role whose members may fund auto-registration drops, unless the DAO config says otherwise.
+    pub council_role: String,
+    /// Static gas attached to view calls on the DAO and SBT registry. With 0, the default,
+    /// calls share the gas left by the claim by weight, which fits Keypom's 100 TGas claims.
+    pub view_call_gas: Gas,
+    /// Static gas attached to `add_proposal` and `act_proposal`, 0 to share the gas by weight.
+    pub proposal_call_gas: Gas,
+    /// Who gets the deposit back when a registration fails.
+    pub refund_receiver: RefundReceiver,
+    /// Flow claims go through, the build's default if omitted.
+    #[serde(default)]
+    pub flow: RegistrationFlow,
+    /// Whether to accept claims of drops created against the v1 bot, which send neither
+    /// `keypom_args` nor `funder`. They always go through the v1 flow, with the treasury as their funder.
+    #[serde(default)]
+    pub accept_legacy_calls: bool,
+}
+
+/// How `new_auto_registration` processes a claim.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegistrationFlow {
+    /// Original flow: add the proposal and vote on it right away, trusting Keypom alone.
+    V1,
+    /// Drop configs, optional humanity check and funder authorization against the DAO policy.
+    V2,
+}
+
+impl Default for RegistrationFlow {
+    /// `V1` in builds with the `v1` feature, `V2` otherwise.
+    fn default() -> Self {
+        if cfg!(feature = "v1") {
+            RegistrationFlow::V1
+        } else {
+            RegistrationFlow::V2
+        }
+    }
+}
+
+#[cfg(feature = "mainnet")]
+const NETWORK_DEFAULTS: Option<(&str, &str)> = Some(("v2.keypom.near", "registry.i-am-human.near"));
+#[cfg(feature = "testnet")]
+const NETWORK_DEFAULTS: Option<(&str, &str)> = Some(("v2.keypom.testnet", "registry-v1.gwg-testing.near"));
+#[cfg(not(any(feature = "mainnet", feature = "testnet")))]
+const NETWORK_DEFAULTS: Option<(&str, &str)> = None;
+
+impl ContractConfig {
+    /// Keypom contract, SBT registry and default gas of the network selected by the `mainnet`
+    /// or `testnet` feature. Sandbox builds have no defaults.
+    pub fn network_default() -> Option<Self> {
+        NETWORK_DEFAULTS.map(|(keypom_contract, sbt_registry)| ContractConfig {
+            keypom_contract: keypom_contract.parse().unwrap(),
+            sbt_registry: sbt_registry.parse().unwrap(),
+            council_role: "council".to_string(),
+            view_call_gas: Gas(0),
+            proposal_call_gas: Gas(0),
+            refund_receiver: RefundReceiver::Funder,
+            flow: RegistrationFlow::default(),
+            accept_legacy_calls: false,
+        })
+    }
+}
@@ -0,0 +1,130 @@
+use crate::*;
+
+/// How the bot decides whether a funder may authorize proposals on a DAO.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone)]
+pub enum FunderAuthorization {
+    /// Funder must be in the group of at least one of these roles.
+    Roles(Vec<String>),
+    /// Funder must hold the sputnikdao2 permission for `action` on the proposal's kind,
+    /// i.e `add_member_to_role:AddProposal`, through any role of the DAO policy.
+    Permissions { action: Action },
+}
+
+/// Actions the bot must be allowed to take on a proposal kind to auto-register with it.
+pub const BOT_REQUIRED_ACTIONS: [Action; 2] = [Action::AddProposal, Action::VoteApprove];
+
+/// Result of `check_dao_compatibility`.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DaoCompatibility {
+    pub compatible: bool,
+    /// `<proposal_kind>:<action>` permissions the bot is missing on the DAO.
+    pub missing_permissions: Vec<String>,
+}
+
+/// Per-DAO settings, set by the DAO itself or a config admin.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone)]
+pub struct DaoConfig {
+    pub authorization: FunderAuthorization,
+    /// SBTs every claimer must hold to join this DAO, unless the drop sets its own.
+    pub human_requirement: Option<HumanRequirement>,
+    /// When not empty, `AddMemberToRole` claims join the role of the first mapping matched
+    /// by the claimer's SBTs instead of the role set in the drop.
+    #[serde(default)]
+    pub role_mappings: Vec<RoleMapping>,
+}
+
+/// DAO config as stored on the bot.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone)]
+pub struct DaoConfigRecord {
+    /// Account that paid for the config's storage, refunded when it is replaced or removed.
+    pub payer: AccountId,
+    pub config: DaoConfig,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Set the config of `dao_contract`. Callable by the DAO itself, i.e through a FunctionCall
+    /// proposal, or by a config admin. The caller pays for storage, and whoever paid for the
+    /// config it replaces gets that storage refunded.
+    #[payable]
+    pub fn set_dao_config(&mut self, dao_contract: AccountId, config: DaoConfig) {
+        require!(env::attached_deposit() > 0, "ATTACH A DEPOSIT TO COVER STORAGE");
+        self.assert_dao_admin(&dao_contract);
+        let payer = env::predecessor_account_id();
+        self.internal_remove_dao_config(&dao_contract);
+
+        let initial_storage = env::storage_usage();
+        self.dao_configs.insert(&dao_contract, &DaoConfigRecord { payer: payer.clone(), config });
+        self.internal_settle_storage(initial_storage, &payer);
+    }
+
+    /// Go back to the default config for `dao_contract`, refunding the freed storage to whoever paid for it.
+    #[payable]
+    pub fn remove_dao_config(&mut self, dao_contract: AccountId) {
+        assert_one_yocto();
+        self.assert_dao_admin(&dao_contract);
+        require!(self.internal_remove_dao_config(&dao_contract), "NO CONFIG SET FOR THIS DAO");
+    }
+
+    /// Check that the bot holds the permissions it needs on `dao_contract` for each policy label in
+    /// `proposal_kinds`, `add_member_to_role` by default. Meant for DAO admins to run before creating drops.
+    pub fn check_dao_compatibility(&self, dao_contract: AccountId, proposal_kinds: Option<Vec<String>>) -> Promise {
+        ext_dao::ext(dao_contract)
+        .with_static_gas(self.config.view_call_gas)
+        .get_policy()
+        .then(
+            Self::ext(env::current_account_id())
+            .callback_dao_compatibility(proposal_kinds.unwrap_or_else(|| vec!["add_member_to_role".to_string()]))
+        )
+    }
+
+    #[private]
+    pub fn callback_dao_compatibility(&self, proposal_kinds: Vec<String>) -> DaoCompatibility {
+        let pol = match env::promise_result(0) {
+            PromiseResult::Successful(val) => near_sdk::serde_json::from_slice::<Policy>(&val)
+                .unwrap_or_else(|_| env::panic_str("ERR_WRONG_VAL_RECEIVED")),
+            _ => env::panic_str("PROBLEM WITH PROMISE"),
+        };
+
+        let bot = env::current_account_id();
+        let missing_permissions = proposal_kinds.iter()
+            .flat_map(|kind| pol.missing_permissions(&bot, kind, &BOT_REQUIRED_ACTIONS))
+            .collect::<Vec<String>>();
+        DaoCompatibility { compatible: missing_permissions.is_empty(), missing_permissions }
+    }
+
+    /// Config in effect for `dao_contract`, the default one if none was set.
+    pub fn view_dao_config(&self, dao_contract: AccountId) -> DaoConfig {
+        self.internal_dao_config(&dao_contract)
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_dao_config(&self, dao_contract: &AccountId) -> DaoConfig {
+        self.dao_configs.get(dao_contract).map(|record| record.config).unwrap_or_else(|| DaoConfig {
+            authorization: FunderAuthorization::Roles(vec![self.config.council_role.clone()]),
+            human_requirement: None,
+            role_mappings: vec![],
+        })
+    }
+
+    /// Remove the stored config of `dao_contract`, if any, and refund its storage to the payer.
+    fn internal_remove_dao_config(&mut self, dao_contract: &AccountId) -> bool {
+        let initial_storage = env::storage_usage();
+        let record = match self.dao_configs.remove(dao_contract) {
+            Some(record) => record,
+            None => return false,
+        };
+
+        let refund = Balance::from(initial_storage - env::storage_usage()) * env::storage_byte_cost();
+        if refund > 0 {
+            Promise::new(record.payer).transfer(refund);
+        }
+        true
+    }
+
+    fn assert_dao_admin(&self, dao_contract: &AccountId) {
+        let caller = env::predecessor_account_id();
+        require!(caller == *dao_contract || self.has_role(&caller, AdminRole::ConfigAdmin), "ONLY THE DAO OR A CONFIG ADMIN CAN CHANGE ITS CONFIG");
+    }
+}
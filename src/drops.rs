@@ -0,0 +1,113 @@
+use crate::*;
+
+/// Keypom drop ID.
+pub type DropId = u128;
+
+/// Registration rules a funder sets up for one of their drops.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone)]
+pub struct DropConfig {
+    /// DAO that claims of this drop must target.
+    pub dao_contract: AccountId,
+    /// Roles that claims may target. Empty allows any role.
+    pub allowed_roles: Vec<String>,
+    /// Whether the claiming account must pass the Proof-of-Humanity check.
+    pub human_only: bool,
+    /// SBTs the claiming account must hold on top of the DAO's requirement. Implies `human_only`.
+    /// Its `registry` must be unset, claims always query the DAO's registry.
+    pub human_requirement: Option<HumanRequirement>,
+    /// Maximum number of claims the bot will process for this drop.
+    pub max_registrations: Option<u64>,
+    /// Unix epoch in milliseconds after which claims are rejected.
+    pub expires_at: Option<u64>,
+}
+
+/// Drop config as stored on the bot.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone)]
+pub struct DropRecord {
+    /// Account that registered the config, claims only use it if Keypom injects the same funder.
+    pub funder: AccountId,
+    pub config: DropConfig,
+    /// Number of claims processed so far.
+    pub registrations: u64,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Register, or update, the config of a drop. Configs are stored per funder, so only the funder
+    /// Keypom injects into claims of the drop can provide its config. The caller pays for storage.
+    #[payable]
+    pub fn register_drop_config(&mut self, drop_id: U128, config: DropConfig) {
+        let initial_storage = env::storage_usage();
+        let funder = env::predecessor_account_id();
+        let key = (funder.clone(), drop_id.0);
+        require!(config.human_requirement.as_ref().is_none_or(|requirement| requirement.registry.is_none()), "ONLY DAO CONFIGS MAY SET THE SBT REGISTRY");
+
+        let registrations = self.drop_configs.get(&key).map(|record| record.registrations).unwrap_or(0);
+        self.drop_configs.insert(&key, &DropRecord { funder: funder.clone(), config, registrations });
+
+        self.internal_settle_storage(initial_storage, &funder);
+    }
+
+    /// Remove one of the caller's drop configs, refunding the freed storage.
+    pub fn remove_drop_config(&mut self, drop_id: U128) {
+        let initial_storage = env::storage_usage();
+        let funder = env::predecessor_account_id();
+
+        require!(self.drop_configs.remove(&(funder.clone(), drop_id.0)).is_some(), "DROP NOT REGISTERED");
+
+        self.internal_settle_storage(initial_storage, &funder);
+    }
+
+    pub fn view_drop_config(&self, funder: AccountId, drop_id: U128) -> Option<DropRecord> {
+        self.drop_configs.get(&(funder, drop_id.0))
+    }
+}
+
+impl Contract {
+    /// Check a claim against the stored drop config and count it towards the drop's cap.
+    pub(crate) fn internal_use_drop(&mut self, drop_id: DropId, funder: &AccountId, dao_contract: &AccountId, kind: &ProposalKind) -> DropConfig {
+        let key = (funder.clone(), drop_id);
+        let mut record = self.drop_configs.get(&key).unwrap_or_else(|| env::panic_str("DROP NOT REGISTERED, FUNDER MUST CALL register_drop_config"));
+        require!(record.config.dao_contract == *dao_contract, "DAO DOES NOT MATCH DROP CONFIG");
+
+        if let Some(role) = kind.role() {
+            require!(record.config.allowed_roles.is_empty() || record.config.allowed_roles.iter().any(|r| r == role), "ROLE NOT ALLOWED BY DROP CONFIG");
+        }
+        if let Some(expires_at) = record.config.expires_at {
+            require!(env::block_timestamp_ms() < expires_at, "DROP CONFIG EXPIRED");
+        }
+        if let Some(max_registrations) = record.config.max_registrations {
+            require!(record.registrations < max_registrations, "DROP REGISTRATION CAP REACHED");
+        }
+
+        record.registrations += 1;
+        self.drop_configs.insert(&key, &record);
+        record.config
+    }
+
+    /// Whether the drop a registration was claimed from allows `role`.
+    pub(crate) fn internal_drop_allows_role(&self, registration_id: u64, role: &str) -> bool {
+        self.registrations.get(&registration_id)
+            .and_then(|registration| self.drop_configs.get(&(registration.funder, registration.drop_id.0)))
+            .is_none_or(|record| record.config.allowed_roles.is_empty() || record.config.allowed_roles.iter().any(|r| r == role))
+    }
+
+    /// Charge `account_id` for storage added since `initial_storage` out of the attached deposit,
+    /// or refund it for storage freed, and return any excess deposit.
+    pub(crate) fn internal_settle_storage(&self, initial_storage: u64, account_id: &AccountId) {
+        let final_storage = env::storage_usage();
+        let attached = env::attached_deposit();
+
+        let refund = if final_storage >= initial_storage {
+            let cost = Balance::from(final_storage - initial_storage) * env::storage_byte_cost();
+            require!(attached >= cost, format!("ATTACH AT LEAST {} YOCTO TO COVER STORAGE", cost));
+            attached - cost
+        } else {
+            attached + Balance::from(initial_storage - final_storage) * env::storage_byte_cost()
+        };
+
+        if refund > 0 {
+            Promise::new(account_id.clone()).transfer(refund);
+        }
+    }
+}
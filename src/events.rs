@@ -0,0 +1,120 @@
+use crate::*;
+use near_sdk::serde_json::json;
+
+pub const EVENT_STANDARD: &str = "keypom_dao_bot";
+pub const EVENT_VERSION: &str = "1.0.0";
+
+/// NEP-297 events emitted along a registration. `registration_id` correlates every
+/// event of one onboarding attempt across the promise chain. Pause changes are logged too.
+#[derive(Serialize, Debug)]
+#[serde(untagged)]
+pub enum DaoBotEvent {
+    RegistrationStarted {
+        registration_id: u64,
+        dao_contract: AccountId,
+        funder: AccountId,
+        drop_id: U128,
+        member_id: Option<AccountId>,
+        proposal_kind: String,
+        human_only: bool,
+    },
+    HumanCheckPassed {
+        registration_id: u64,
+        member_id: AccountId,
+        /// Tokens that satisfied the drop or DAO requirement, empty for a plain `is_human` check.
+        tokens: Vec<SatisfyingToken>,
+    },
+    HumanCheckFailed {
+        registration_id: u64,
+        member_id: AccountId,
+        reason: String,
+    },
+    RoleResolved {
+        registration_id: u64,
+        member_id: AccountId,
+        role: String,
+        /// Token that matched the role mapping.
+        token: SatisfyingToken,
+    },
+    BotPermissionCheckFailed {
+        registration_id: u64,
+        dao_contract: AccountId,
+        missing_permissions: Vec<String>,
+    },
+    RoleCheckFailed {
+        registration_id: u64,
+        dao_contract: AccountId,
+        role: String,
+        reason: String,
+    },
+    CouncilCheckPassed {
+        registration_id: u64,
+        funder: AccountId,
+    },
+    CouncilCheckFailed {
+        registration_id: u64,
+        funder: AccountId,
+        reason: String,
+    },
+    ProposalAdded {
+        registration_id: u64,
+        dao_contract: AccountId,
+        proposal_id: u64,
+    },
+    ProposalApproved {
+        registration_id: u64,
+        dao_contract: AccountId,
+        proposal_id: u64,
+    },
+    AlreadyMember {
+        registration_id: u64,
+        dao_contract: AccountId,
+        member_id: AccountId,
+        role: String,
+    },
+    RegistrationFailed {
+        registration_id: u64,
+        reason: String,
+        refund_receiver: Option<AccountId>,
+        refund: U128,
+    },
+    Paused {
+        scope: PauseScope,
+        by: AccountId,
+    },
+    Unpaused {
+        scope: PauseScope,
+        by: AccountId,
+    },
+}
+
+impl DaoBotEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            DaoBotEvent::RegistrationStarted { .. } => "registration_started",
+            DaoBotEvent::HumanCheckPassed { .. } => "human_check_passed",
+            DaoBotEvent::HumanCheckFailed { .. } => "human_check_failed",
+            DaoBotEvent::RoleResolved { .. } => "role_resolved",
+            DaoBotEvent::BotPermissionCheckFailed { .. } => "bot_permission_check_failed",
+            DaoBotEvent::RoleCheckFailed { .. } => "role_check_failed",
+            DaoBotEvent::CouncilCheckPassed { .. } => "council_check_passed",
+            DaoBotEvent::CouncilCheckFailed { .. } => "council_check_failed",
+            DaoBotEvent::ProposalAdded { .. } => "proposal_added",
+            DaoBotEvent::ProposalApproved { .. } => "proposal_approved",
+            DaoBotEvent::AlreadyMember { .. } => "already_member",
+            DaoBotEvent::RegistrationFailed { .. } => "registration_failed",
+            DaoBotEvent::Paused { .. } => "paused",
+            DaoBotEvent::Unpaused { .. } => "unpaused",
+        }
+    }
+
+    pub fn emit(&self) {
+        let log = json!({
+            "standard": EVENT_STANDARD,
+            "version": EVENT_VERSION,
+            "event": self.name(),
+            "data": [self],
+        });
+        log!("EVENT_JSON:{}", log);
+    }
+}
@@ -1,74 +1,43 @@
-mod ext_traits;
+mod acl;
+mod bonds;
+mod config;
+mod daos;
+mod drops;
+mod events;
+mod metadata;
+mod pause;
+mod refunds;
+mod registrations;
+mod sbt;
+mod upgrade;
+mod v1;
+#[cfg(test)]
+mod tests;
 
-use ext_traits::{ext_dao, ext_sbt_registry};
+pub use dao_bot_types::*;
+use dao_bot_types::ext_traits::{ext_dao, ext_sbt_registry};
+pub use acl::*;
+pub use bonds::*;
+pub use config::*;
+pub use daos::*;
+pub use drops::*;
+pub use events::*;
+pub use metadata::*;
+pub use pause::*;
+pub use refunds::*;
+pub use registrations::*;
+pub use sbt::*;
+pub use upgrade::*;
+pub use v1::*;
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::{log, near_bindgen, AccountId, Gas, env, Promise, PromiseResult, require, Balance};
+use near_sdk::{assert_one_yocto, log, near_bindgen, AccountId, Gas, env, Promise, PromiseResult, require, Balance, PanicOnDefault, BorshStorageKey, PromiseOrValue, CryptoHash};
+use near_sdk::collections::{LookupMap, UnorderedMap, UnorderedSet, Vector};
 use near_sdk::serde::{Deserialize, Serialize};
-use std::convert::TryFrom;
-use std::collections::HashSet;
-use near_sdk::json_types::{U128, Base64VecU8};
+use near_sdk::json_types::{U128, U64};
 
 pub const XCC_GAS: Gas = Gas(20_000_000_000_000);
 pub const TGAS: u64 = 1_000_000_000_000;
 
-// 0.1 $NEAR
-pub const SPUTNIK_PROPOSAL_DEPOSIT: Balance = 100000000000000000000000;
-
-#[derive(Serialize, Deserialize, Debug)]
-pub struct ProposalInput {
-    /// Description of this proposal.
-    pub description: String,
-    /// Kind of proposal with relevant information.
-    pub kind: ProposalKind,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub enum ProposalKind {
-    /// Add member to given role in the policy. This is short cut to updating the whole policy.
-    AddMemberToRole { member_id: AccountId, role: String },
-}
-
-#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
-pub struct Policy {
-    /// List of roles and permissions for them in the current policy.
-    pub roles: Vec<RolePermission>,
-}
-
-#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize,)]
-pub struct RolePermission {
-    /// Name of the role to display to the user.
-    pub name: String,
-    /// Kind of the role: defines which users this permissions apply.
-    pub kind: RoleKind
-}
-
-#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
-pub enum RoleKind {
-    /// Matches everyone, who is not matched by other roles.
-    Everyone,
-    /// Member greater or equal than given balance. Can use `1` as non-zero balance.
-    Member(U128),
-    /// Set of accounts.
-    Group(HashSet<AccountId>),
-}
-
-#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
-pub struct OwnedToken {
-    pub token: TokenId,
-    pub metadata: TokenMetadata,
-}
-
-#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
-pub struct TokenMetadata {
-    pub class: ClassId,                      // token class
-    pub issued_at: Option<u64>, // When token was issued or minted, Unix epoch in milliseconds
-    pub expires_at: Option<u64>, // When token expires, Unix epoch in milliseconds
-    pub reference: Option<String>, // URL to an off-chain JSON file with more info.
-    pub reference_hash: Option<Base64VecU8>, // Base64-encoded sha256 hash of JSON from reference field. Required if `reference` is included.
-}
-
-pub type ClassId = u64;
-pub type TokenId = u64;
 
 /// Injected Keypom Args struct to be sent to external contracts
 #[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug)]
@@ -80,168 +49,407 @@ pub struct KeypomArgs {
     pub funder_id_field: Option<String>
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug)]
-pub enum Action {
-    /// Action to add proposal. Used internally.
-    AddProposal,
-    // Action to remove given proposal. Used for immediate deletion in special cases.
-    RemoveProposal,
-    /// Vote to approve given proposal or bounty.
-    VoteApprove,
-    /// Vote to reject given proposal or bounty.
-    VoteReject,
-    /// Vote to remove given proposal or bounty (because it's spam).
-    VoteRemove,
-    /// Finalize proposal, called when it's expired to return the funds
-    /// (or in the future can be used for early proposal closure).
-    Finalize,
-}
-
 // Define the contract structure
 #[near_bindgen]
-#[derive(BorshDeserialize, BorshSerialize)]
+#[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
 pub struct Contract {
-    keypom_contract: AccountId
+    owner_id: AccountId,
+    pending_owner: Option<AccountId>,
+    admins: UnorderedMap<AccountId, Vec<AdminRole>>,
+    config: ContractConfig,
+    drop_configs: LookupMap<(AccountId, DropId), DropRecord>,
+    balances: LookupMap<AccountId, Balance>,
+    bonds: LookupMap<(AccountId, u64), BondRecord>,
+    next_registration_id: u64,
+    registrations: LookupMap<u64, Registration>,
+    registrations_by_dao: LookupMap<AccountId, Vector<u64>>,
+    registrations_by_member: LookupMap<AccountId, Vector<u64>>,
+    registrations_by_funder: LookupMap<AccountId, Vector<u64>>,
+    dao_configs: LookupMap<AccountId, DaoConfigRecord>,
+    pending_registrations: UnorderedSet<u64>,
+    paused: bool,
+    paused_daos: UnorderedSet<AccountId>,
+    paused_drops: UnorderedSet<DropId>
 }
 
-impl Default for Contract{
-    fn default() -> Self{
-        Self{
-            keypom_contract: AccountId::try_from("v2.keypom.near".to_string()).unwrap()
-        }
-    }
+#[derive(BorshSerialize, BorshStorageKey)]
+pub(crate) enum StorageKey {
+    Admins,
+    DropConfigs,
+    Balances,
+    Bonds,
+    Registrations,
+    RegistrationsByDao,
+    RegistrationsByDaoInner(CryptoHash),
+    RegistrationsByMember,
+    RegistrationsByMemberInner(CryptoHash),
+    RegistrationsByFunder,
+    RegistrationsByFunderInner(CryptoHash),
+    DaoConfigs,
+    PendingRegistrations,
+    PausedDaos,
+    PausedDrops,
 }
 
 // Implement the contract structure
 #[near_bindgen]
 impl Contract {
 
+    /// Initialize the bot. `config` defaults to the network selected at build time, if any.
+    #[init]
+    #[private]
+    pub fn new(owner_id: AccountId, config: Option<ContractConfig>) -> Self {
+        let config = config.or_else(ContractConfig::network_default)
+            .unwrap_or_else(|| env::panic_str("CONFIG REQUIRED, THIS BUILD HAS NO NETWORK DEFAULTS"));
+        Self::internal_write_state_version();
+        Self{
+            owner_id,
+            pending_owner: None,
+            admins: UnorderedMap::new(StorageKey::Admins),
+            config,
+            drop_configs: LookupMap::new(StorageKey::DropConfigs),
+            balances: LookupMap::new(StorageKey::Balances),
+            bonds: LookupMap::new(StorageKey::Bonds),
+            next_registration_id: 0,
+            registrations: LookupMap::new(StorageKey::Registrations),
+            registrations_by_dao: LookupMap::new(StorageKey::RegistrationsByDao),
+            registrations_by_member: LookupMap::new(StorageKey::RegistrationsByMember),
+            registrations_by_funder: LookupMap::new(StorageKey::RegistrationsByFunder),
+            dao_configs: LookupMap::new(StorageKey::DaoConfigs),
+            pending_registrations: UnorderedSet::new(StorageKey::PendingRegistrations),
+            paused: false,
+            paused_daos: UnorderedSet::new(StorageKey::PausedDaos),
+            paused_drops: UnorderedSet::new(StorageKey::PausedDrops)
+        }
+    }
+
+    /// Entry point of Keypom FC drops. `keypom_args` and `funder` are omitted by drops created
+    /// against the v1 bot, whose claims go through the v1 flow if `accept_legacy_calls` is set.
     #[payable]
-    pub fn new_auto_registration(&mut self, dao_contract: AccountId, keypom_args: KeypomArgs, funder: AccountId, proposal: ProposalInput, human_only: Option<bool>) {
+    pub fn new_auto_registration(&mut self, dao_contract: AccountId, keypom_args: Option<KeypomArgs>, funder: Option<AccountId>, proposal: ProposalInput, human_only: Option<bool>, drop_id: Option<U128>) {
         // Ensure Keypom called this function 
-        require!(env::predecessor_account_id() == self.keypom_contract.clone(), "KEYPOM MUST BE PREDECESSOR, CHECK REQUIRED VERSION USING view_keypom_contract");
-        
-        // Note since ONLY AddMemberToRole defined from proposal.kind, any other proposal types will result in serialization error!
-        require!(keypom_args.funder_id_field == Some("funder".to_string()) && keypom_args.account_id_field == Some("proposal.kind.AddMemberToRole.member_id".to_string()), "KEYPOM MUST SEND THESE ARGS");
-
-        // Ensure enough attached deposit was added to add the proposal
-        require!(env::attached_deposit() >= SPUTNIK_PROPOSAL_DEPOSIT, "ATTACH MORE NEAR, AT LEAST 0.1 $NEAR");
-
-        // Ensure proposal kind is valid
-        match &proposal.kind{
-            // extract member ID here using match statement
-            ProposalKind::AddMemberToRole { member_id, role: _ } => {
-                // If Proof-of-Humanity required, begin check
-                if human_only.unwrap_or(false) {
-                    ext_sbt_registry::ext(AccountId::try_from("registry.i-am-human.near".to_string()).unwrap())
-                       .is_human(member_id.clone())
-                       .then(
-                            Self::ext(env::current_account_id())
-                            .internal_human_check(funder, proposal, dao_contract)
-                        );
-                }
-                // If no humanity proof required, start check right away.
-                else{
-                    // Begin auto-registration
-                    ext_dao::ext(AccountId::try_from(dao_contract.clone().to_string()).unwrap())
-                    .get_policy()
-                    .then(
-                        Self::ext(env::current_account_id())
-                        .internal_get_roles_callback(funder, proposal, dao_contract)
-                    );
-                }
+        require!(env::predecessor_account_id() == self.config.keypom_contract, "KEYPOM MUST BE PREDECESSOR, CHECK REQUIRED VERSION USING view_keypom_contract");
+
+        let (keypom_args, funder) = match (keypom_args, funder) {
+            (Some(keypom_args), Some(funder)) => (keypom_args, funder),
+            // Legacy v1 argument shape. These drops registered no config, so their bonds and refunds go to
+            // the treasury, which never tops up their deposit
+            (None, None) => {
+                require!(self.config.accept_legacy_calls, "LEGACY CALLS DISABLED, KEYPOM MUST SEND keypom_args AND funder");
+                self.internal_v1_registration(dao_contract, env::current_account_id(), drop_id.unwrap_or(U128(0)), proposal);
+                return;
             }
+            // Anything else, including v2 drops without a drop id, must go through the checked flow
+            _ => env::panic_str("KEYPOM MUST SEND THESE ARGS"),
+        };
+
+        // Keypom may only inject the claiming account into the field designated for this proposal kind
+        require!(keypom_args.funder_id_field == Some("funder".to_string()) && keypom_args.drop_id_field == Some("drop_id".to_string()) && keypom_args.account_id_field == proposal.kind.claimer_field_path(), "KEYPOM MUST SEND THESE ARGS");
+
+        // The v1 flow skips every check below
+        if self.config.flow == RegistrationFlow::V1 {
+            self.internal_v1_registration(dao_contract, funder, drop_id.unwrap_or(U128(0)), proposal);
+            return;
+        }
+
+        // Enforce the funder's stored drop config, the stricter humanity requirement wins.
+        // A drop's human requirement applies on top of the DAO's, and either one forces the check.
+        let drop_id = drop_id.unwrap_or_else(|| env::panic_str("KEYPOM MUST SEND THESE ARGS"));
+        require!(!self.internal_is_paused(&dao_contract, drop_id.0), "REGISTRATIONS PAUSED");
+        let drop_config = self.internal_use_drop(drop_id.0, &funder, &dao_contract, &proposal.kind);
+        let dao_config = self.internal_dao_config(&dao_contract);
+        let drop_requirement = drop_config.human_requirement.map(|requirement| HumanRequirement { registry: None, ..requirement });
+        let human_only = drop_config.human_only || human_only.unwrap_or(false) || drop_requirement.is_some() || dao_config.human_requirement.is_some();
+
+        // DAOs with a role mapping resolve the target role from the claimer's SBTs, on top of the humanity check
+        let role_mappings = match proposal.kind {
+            ProposalKind::AddMemberToRole { .. } => dao_config.role_mappings,
+            _ => vec![],
+        };
+        let dao_requirement = dao_config.human_requirement;
+
+        // What's left after storage is checked against the DAO's proposal bond once its policy is fetched
+        let (registration_id, deposit) = self.internal_create_registration(&dao_contract, &funder, drop_id, &proposal.kind, env::attached_deposit());
+        let deposit = U128(deposit);
+        DaoBotEvent::RegistrationStarted {
+            registration_id,
+            dao_contract: dao_contract.clone(),
+            funder: funder.clone(),
+            drop_id,
+            member_id: proposal.kind.claimer().cloned(),
+            proposal_kind: proposal.kind.name().to_string(),
+            human_only,
+        }.emit();
+
+        // If Proof-of-Humanity or role resolution required, begin check on the claiming account
+        if human_only || !role_mappings.is_empty() {
+            let member_id = proposal.kind.claimer().cloned().unwrap_or_else(|| env::panic_str("HUMAN CHECK REQUIRES A PROPOSAL KIND WITH A CLAIMING ACCOUNT"));
+            let registry = dao_requirement.as_ref()
+                .and_then(|requirement| requirement.registry.clone())
+                .unwrap_or_else(|| self.config.sbt_registry.clone());
+            let sbt_check = SbtCheck {
+                is_human: human_only && dao_requirement.is_none() && drop_requirement.is_none(),
+                dao_requirement,
+                drop_requirement,
+                role_mappings,
+            };
+            let registry_call = || ext_sbt_registry::ext(registry.clone()).with_static_gas(self.config.view_call_gas);
+            // Without configured requirements any humanity proof from the default registry will do
+            let proof = sbt_check.is_human.then(|| registry_call().is_human(member_id.clone()));
+            // Configured requirements and role mappings are evaluated against the claimer's tokens
+            let tokens = sbt_check.fetches_tokens()
+                .then(|| registry_call().sbt_tokens_by_owner(member_id, sbt_check.issuer_filter(), None, None, Some(false)));
+            let human_check = proof.into_iter().chain(tokens).reduce(Promise::and)
+                .unwrap_or_else(|| env::panic_str("NO SBT CHECK TO RUN"));
+            human_check.then(
+                Self::ext(env::current_account_id())
+                .internal_human_check(registration_id, funder, proposal, dao_contract, deposit, sbt_check)
+            );
+        }
+        // If no humanity proof required, start check right away.
+        else{
+            // Begin auto-registration
+            ext_dao::ext(dao_contract.clone())
+            .with_static_gas(self.config.view_call_gas)
+            .get_policy()
+            .then(
+                Self::ext(env::current_account_id())
+                .internal_get_roles_callback(registration_id, funder, proposal, dao_contract, deposit)
+            );
         }
     } 
 
     #[private]
-    pub fn internal_human_check(funder: AccountId, proposal: ProposalInput, dao_contract: AccountId) {
-         // Parse Response and Check if Fractal is in owned tokens
-        if let PromiseResult::Successful(val) = env::promise_result(0) {
-            if let Ok(proof) = near_sdk::serde_json::from_slice::<Vec<(AccountId, Vec<ClassId>)>>(&val) {
-                let mut human_tokens = proof.into_iter().peekable();
-                log!("New Human Check");
-                require!(human_tokens.peek().is_none() == false, "CLAIMING ACCOUNT MUST BE HUMAN");
-                
-                // Begin auto-registration
-                ext_dao::ext(AccountId::try_from(dao_contract.clone().to_string()).unwrap())
-                .get_policy()
-                .then(
-                    Self::ext(env::current_account_id())
-                    .internal_get_roles_callback(funder, proposal, dao_contract)
-                );
-            } else {
-             env::panic_str("ERR_WRONG_VAL_RECEIVED")
-            }      
+    pub fn internal_human_check(&mut self, registration_id: u64, funder: AccountId, proposal: ProposalInput, dao_contract: AccountId, deposit: U128, sbt_check: SbtCheck) -> Promise {
+        let mut proposal = proposal;
+        if self.internal_registration_paused(registration_id) {
+            return self.internal_fail_registration(registration_id, &funder, deposit.0, "REGISTRATIONS PAUSED");
         }
-        else{
-            env::panic_str("PROBLEM WITH PROMISE")
-        }  
+        let member_id = proposal.kind.claimer().cloned().unwrap_or_else(|| env::panic_str("HUMAN CHECK REQUIRES A PROPOSAL KIND WITH A CLAIMING ACCOUNT"));
+        let now = env::block_timestamp_ms();
+
+        // Parse the humanity proof and the claimer's tokens, checking the configured requirements are met
+        let result = sbt_check.evaluate(now);
+        let human_checked = sbt_check.is_human || sbt_check.requirements().next().is_some();
+        let tokens = match result {
+            Ok((satisfying, tokens)) => {
+                if human_checked {
+                    DaoBotEvent::HumanCheckPassed { registration_id, member_id: member_id.clone(), tokens: satisfying }.emit();
+                    self.internal_update_registration(registration_id, RegistrationStatus::HumanCheckPassed, None, None);
+                }
+                tokens
+            }
+            Err(reason) => {
+                if human_checked {
+                    DaoBotEvent::HumanCheckFailed { registration_id, member_id, reason: reason.to_string() }.emit();
+                }
+                return self.internal_fail_registration(registration_id, &funder, deposit.0, reason);
+            }
+        };
+
+        // Replace the drop's static role with the one mapped from the claimer's SBTs
+        if !sbt_check.role_mappings.is_empty() {
+            let validity = sbt_check.dao_requirement.unwrap_or_default();
+            let (role, token) = match resolve_role(&sbt_check.role_mappings, &tokens, |metadata| validity.is_token_valid(metadata, now)) {
+                Some(resolved) => resolved,
+                None => return self.internal_fail_registration(registration_id, &funder, deposit.0, "NO ROLE MAPPING MATCHES CLAIMING ACCOUNT'S SBTS"),
+            };
+            if !self.internal_drop_allows_role(registration_id, &role) {
+                return self.internal_fail_registration(registration_id, &funder, deposit.0, "ROLE NOT ALLOWED BY DROP CONFIG");
+            }
+            DaoBotEvent::RoleResolved { registration_id, member_id, role: role.clone(), token }.emit();
+            self.internal_set_registration_role(registration_id, &role);
+            proposal.kind.set_role(role);
+        }
+
+        // Begin auto-registration
+        ext_dao::ext(dao_contract.clone())
+        .with_static_gas(self.config.view_call_gas)
+        .get_policy()
+        .then(
+            Self::ext(env::current_account_id())
+            .internal_get_roles_callback(registration_id, funder, proposal, dao_contract, deposit)
+        )
     }
 
     
     // Roles callback, parse and return council role(s)
     #[private]
-    pub fn internal_get_roles_callback(&mut self, funder: AccountId, proposal: ProposalInput, dao_contract: AccountId){
+    pub fn internal_get_roles_callback(&mut self, registration_id: u64, funder: AccountId, proposal: ProposalInput, dao_contract: AccountId, deposit: U128) -> Promise {
+        if self.internal_registration_paused(registration_id) {
+            return self.internal_fail_registration(registration_id, &funder, deposit.0, "REGISTRATIONS PAUSED");
+        }
+
         // Receive get_policy promise, parse it and see if funder is on DAO council
-        if let PromiseResult::Successful(val) = env::promise_result(0) {
-                if let Ok(pol) = near_sdk::serde_json::from_slice::<Policy>(&val) {
-                    // Trying to collect all roles with name council from policy
-                    let members = pol.roles.into_iter()
-                    .filter(|role| role.name == "council".to_string())
-                    .collect::<Vec<RolePermission>>()
-                    .into_iter()
-                    .nth(0)
-                    .unwrap().kind;
-
-                    // See if funder is in Council group
-                    match members{
-                        RoleKind::Group(set) => {
-                            if set.contains(&AccountId::try_from(funder.to_string()).unwrap()){
-                                // Add proposal to register member if funder is on council
-                                ext_dao::ext(AccountId::try_from(dao_contract.clone().to_string()).unwrap())
-                                .with_attached_deposit(SPUTNIK_PROPOSAL_DEPOSIT)
-                                .add_proposal(proposal)
-                                .then(
-                                    Self::ext(env::current_account_id())
-                                    .callback_new_auto_registration(dao_contract)
-                                );   
-                            }
-                            else{
-                                log!("Funder is not council!");
-                            }
-                        }
-                        _ => (),
-                    };
-            } else {
-                env::panic_str("ERR_WRONG_VAL_RECEIVED")
+        let pol = match env::promise_result(0) {
+            PromiseResult::Successful(val) => match near_sdk::serde_json::from_slice::<Policy>(&val) {
+                Ok(pol) => pol,
+                Err(_) => return self.internal_fail_registration(registration_id, &funder, deposit.0, "ERR_WRONG_VAL_RECEIVED"),
+            },
+            _ => return self.internal_fail_registration(registration_id, &funder, deposit.0, "PROBLEM WITH PROMISE"),
+        };
+
+        // Make sure the bot itself can add and approve this kind of proposal before spending the bond
+        let missing_permissions = pol.missing_permissions(&env::current_account_id(), proposal.kind.to_policy_label(), &BOT_REQUIRED_ACTIONS);
+        if !missing_permissions.is_empty() {
+            let reason = format!("BOT LACKS PERMISSIONS {}", missing_permissions.join(", "));
+            DaoBotEvent::BotPermissionCheckFailed { registration_id, dao_contract: dao_contract.clone(), missing_permissions }.emit();
+            return self.internal_fail_registration(registration_id, &funder, deposit.0, &reason);
+        }
+
+        // Make sure the targeted role exists and is a group role before spending the bond
+        if let Some(role) = proposal.kind.role() {
+            let failure = match pol.role(role) {
+                None => Some(format!("ROLE {} DOES NOT EXIST ON DAO", role)),
+                Some(RolePermission { kind: RoleKind::Group(_), .. }) => None,
+                Some(_) => Some(format!("ROLE {} IS NOT A GROUP ROLE", role)),
+            };
+            if let Some(reason) = failure {
+                DaoBotEvent::RoleCheckFailed { registration_id, dao_contract: dao_contract.clone(), role: role.to_string(), reason: reason.clone() }.emit();
+                return self.internal_fail_registration(registration_id, &funder, deposit.0, &reason);
             }
-        } 
+        }
+
+        // See if funder is allowed to authorize proposals on this DAO
+        let failure = match self.internal_dao_config(&dao_contract).authorization {
+            FunderAuthorization::Roles(roles) => {
+                if roles.iter().any(|role| pol.is_member_of(&funder, role)) {
+                    None
+                } else if roles.iter().all(|role| pol.role(role).is_none()) {
+                    Some("DAO HAS NO COUNCIL ROLE".to_string())
+                } else {
+                    Some("FUNDER IS NOT COUNCIL".to_string())
+                }
+            }
+            FunderAuthorization::Permissions { action } => {
+                if pol.can_execute_action(&funder, proposal.kind.to_policy_label(), action) {
+                    None
+                } else {
+                    Some(format!("FUNDER LACKS {}:{} PERMISSION", proposal.kind.to_policy_label(), action.to_policy_label()))
+                }
+            }
+        };
+        if let Some(reason) = failure {
+            DaoBotEvent::CouncilCheckFailed { registration_id, funder: funder.clone(), reason: reason.clone() }.emit();
+            return self.internal_fail_registration(registration_id, &funder, deposit.0, &reason);
+        }
+        DaoBotEvent::CouncilCheckPassed { registration_id, funder: funder.clone() }.emit();
+        self.internal_update_registration(registration_id, RegistrationStatus::CouncilCheckPassed, None, None);
+
+        // Skip the proposal, and its bond, if the member is already in the target role
+        if let ProposalKind::AddMemberToRole { member_id, role } = &proposal.kind {
+            if pol.is_member_of(member_id, role) {
+                DaoBotEvent::AlreadyMember { registration_id, dao_contract: dao_contract.clone(), member_id: member_id.clone(), role: role.clone() }.emit();
+                self.internal_update_registration(registration_id, RegistrationStatus::AlreadyMember, None, None);
+                return self.internal_refund_deposit(&funder, deposit.0);
+            }
+        }
+
+        // Ensure the deposit covers the DAO's proposal bond, rolling over the funder's credited balance if needed
+        let bond = pol.proposal_bond.0;
+        let attached = deposit.0;
+        let deposit = match self.internal_rollover_deposit(&funder, attached, bond) {
+            Some(deposit) => deposit,
+            None => return self.internal_fail_registration(registration_id, &funder, attached, &format!("ATTACH MORE NEAR, AT LEAST {} YOCTO", bond)),
+        };
+        if deposit > bond {
+            self.internal_refund_deposit(&funder, deposit - bond);
+        }
+
+        // Predict whether the bot's vote alone will pass the proposal
+        let vote_decisive = pol.is_vote_decisive(&env::current_account_id(), &proposal.kind);
+        self.internal_record_vote_prediction(registration_id, vote_decisive);
+
+        // Add proposal to register member if funder is on council
+        ext_dao::ext(dao_contract.clone())
+        .with_attached_deposit(bond)
+        .with_static_gas(self.config.proposal_call_gas)
+        .add_proposal(proposal)
+        .then(
+            Self::ext(env::current_account_id())
+            .callback_new_auto_registration(registration_id, funder, dao_contract, U128(bond), U128(deposit - attached))
+        )
     }
     
+    /// `rolled_over` is the share of `bond` taken from the funder's credited balance, credited back if the proposal fails.
     #[private]
-    pub fn callback_new_auto_registration(&mut self, dao_contract: AccountId) -> Promise{
+    pub fn callback_new_auto_registration(&mut self, registration_id: u64, funder: AccountId, dao_contract: AccountId, bond: U128, rolled_over: U128) -> PromiseOrValue<()>{
         // Get proposal ID from add_proposal promise
         if let PromiseResult::Successful(val) = env::promise_result(0) {
             if let Ok(proposal_id) = near_sdk::serde_json::from_slice::<u64>(&val) {                 
+                DaoBotEvent::ProposalAdded { registration_id, dao_contract: dao_contract.clone(), proposal_id }.emit();
+                self.internal_update_registration(registration_id, RegistrationStatus::ProposalAdded, Some(proposal_id), None);
+
+                // Remember who paid the bond so it can be credited back once the DAO returns it
+                self.bonds.insert(&(dao_contract.clone(), proposal_id), &BondRecord { funder, amount: bond });
+
+                // Leave a proposal added while pausing to the council, its bond is tracked for claim_bond
+                if self.internal_registration_paused(registration_id) {
+                    DaoBotEvent::RegistrationFailed { registration_id, reason: "REGISTRATIONS PAUSED".to_string(), refund_receiver: None, refund: U128(0) }.emit();
+                    self.internal_update_registration(registration_id, RegistrationStatus::Failed, None, Some("REGISTRATIONS PAUSED"));
+                    return PromiseOrValue::Value(());
+                }
+
                 // Approve proposal that was just added 
-                ext_dao::ext(AccountId::try_from(dao_contract.clone().to_string()).unwrap())
+                ext_dao::ext(dao_contract.clone())
+               .with_static_gas(self.config.proposal_call_gas)
                .act_proposal(proposal_id, Action::VoteApprove, Some("Keypom DAO BOT Auto-Registration".to_string()))
+               .then(
+                    Self::ext(env::current_account_id())
+                    .callback_proposal_voted(registration_id, dao_contract, proposal_id)
+               ).into()
             } else {
-                env::panic_str("ERR_WRONG_VAL_RECEIVED")
+                // The proposal was added and its bond is held by the DAO, nothing left to refund
+                DaoBotEvent::RegistrationFailed { registration_id, reason: "ERR_WRONG_VAL_RECEIVED".to_string(), refund_receiver: None, refund: U128(0) }.emit();
+                self.internal_update_registration(registration_id, RegistrationStatus::Failed, None, Some("ERR_WRONG_VAL_RECEIVED"));
+                PromiseOrValue::Value(())
             }
         } 
         else{
-            env::panic_str("PROBLEM WITH PROMISE")
+            // The bond attached to the failed add_proposal came back to the bot. The share rolled over from
+            // the funder's balance goes back there, only the claim's own deposit goes to the refund receiver
+            let rolled_over = rolled_over.0.min(bond.0);
+            if rolled_over > 0 {
+                let balance = self.balances.get(&funder).unwrap_or(0);
+                self.internal_set_balance(&funder, balance + rolled_over);
+            }
+            self.internal_fail_registration(registration_id, &funder, bond.0 - rolled_over, "PROBLEM WITH PROMISE").into()
         }  
     }
 
     #[private]
+    pub fn callback_proposal_voted(&mut self, registration_id: u64, dao_contract: AccountId, proposal_id: u64) -> PromiseOrValue<()> {
+        if let PromiseResult::Successful(_) = env::promise_result(0) {
+            // Read back the proposal to learn whether the vote passed it
+            self.internal_fetch_proposal_status(registration_id, dao_contract, proposal_id).into()
+        } else {
+            // The proposal stays InProgress on the DAO for the council to vote on, keep tracking it
+            log!("Bot vote on proposal {} failed, awaiting council votes", proposal_id);
+            self.pending_registrations.insert(&registration_id);
+            self.internal_update_registration(registration_id, RegistrationStatus::AwaitingVotes, Some(proposal_id), None);
+            PromiseOrValue::Value(())
+        }
+    }
+
+    #[payable]
     pub fn change_keypom_contract(&mut self, new_contract: AccountId){
-        self.keypom_contract = new_contract
+        assert_one_yocto();
+        self.assert_role(AdminRole::ConfigAdmin);
+        self.config.keypom_contract = new_contract
+    }
+
+    #[payable]
+    pub fn change_config(&mut self, config: ContractConfig){
+        assert_one_yocto();
+        self.assert_role(AdminRole::ConfigAdmin);
+        self.config = config
     }
 
     pub fn view_keypom_contract(&self) -> AccountId{
-        self.keypom_contract.clone()
+        self.config.keypom_contract.clone()
+    }
+
+    pub fn view_config(&self) -> ContractConfig{
+        self.config.clone()
     }
 }
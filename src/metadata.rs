@@ -0,0 +1,72 @@
+use crate::*;
+
+/// Repository the bot is built from.
+const SOURCE_LINK: &str = "https://github.com/keypom/dao-bot";
+
+/// NEP-330 contract source metadata.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ContractSourceMetadata {
+    pub version: Option<String>,
+    /// Git commit the wasm was built from, set by `build.sh` through `DAO_BOT_COMMIT`.
+    pub commit: Option<String>,
+    /// Source tree of `commit`, or the repository if unknown.
+    pub link: Option<String>,
+    pub standards: Vec<Standard>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Standard {
+    pub standard: String,
+    pub version: String,
+}
+
+/// What this build of the bot supports, for frontends to feature-detect.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Capabilities {
+    /// Flow new claims currently go through.
+    pub flow: RegistrationFlow,
+    /// Names of the proposal kinds accepted in `new_auto_registration` by the active flow.
+    pub proposal_kinds: Vec<String>,
+    /// `is_human`, `sbt_requirement` and `role_mapping`.
+    pub human_check_modes: Vec<String>,
+    /// `v2` with `keypom_args` and `funder`, and `v1` if legacy calls are accepted.
+    pub keypom_arg_layouts: Vec<String>,
+    pub state_version: StateVersion,
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn contract_source_metadata(&self) -> ContractSourceMetadata {
+        let commit = option_env!("DAO_BOT_COMMIT").map(|commit| commit.to_string());
+        ContractSourceMetadata {
+            version: Some(env!("CARGO_PKG_VERSION").to_string()),
+            link: Some(match &commit {
+                Some(commit) => format!("{}/tree/{}", SOURCE_LINK, commit),
+                None => SOURCE_LINK.to_string(),
+            }),
+            commit,
+            standards: vec![
+                Standard { standard: "nep330".to_string(), version: "1.1.0".to_string() },
+                Standard { standard: "nep297".to_string(), version: "1.0.0".to_string() },
+                Standard { standard: EVENT_STANDARD.to_string(), version: EVENT_VERSION.to_string() },
+            ],
+        }
+    }
+
+    pub fn view_capabilities(&self) -> Capabilities {
+        let mut keypom_arg_layouts = vec!["v2".to_string()];
+        if self.config.accept_legacy_calls {
+            keypom_arg_layouts.push("v1".to_string());
+        }
+        Capabilities {
+            flow: self.config.flow,
+            proposal_kinds: match self.config.flow {
+                RegistrationFlow::V1 => V1_PROPOSAL_KINDS.iter(),
+                RegistrationFlow::V2 => SUPPORTED_PROPOSAL_KINDS.iter(),
+            }.map(|kind| kind.to_string()).collect(),
+            human_check_modes: ["is_human", "sbt_requirement", "role_mapping"].iter().map(|mode| mode.to_string()).collect(),
+            keypom_arg_layouts,
+            state_version: Self::internal_state_version(),
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use crate::*;
+
+/// What a pause applies to.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum PauseScope {
+    /// Every registration.
+    Global,
+    /// Registrations onboarding into `dao_contract`.
+    Dao { dao_contract: AccountId },
+    /// Claims of a single drop.
+    Drop { drop_id: U128 },
+}
+
+/// Pause flags currently set, returned by `view_pause_state`.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PauseState {
+    pub global: bool,
+    pub daos: Vec<AccountId>,
+    pub drops: Vec<U128>,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Stop new registrations in `scope`. In-flight registrations are aborted, and refunded,
+    /// at their next callback. Callable by a pauser.
+    #[payable]
+    pub fn pause(&mut self, scope: PauseScope) {
+        assert_one_yocto();
+        self.assert_role(AdminRole::Pauser);
+        match &scope {
+            PauseScope::Global => self.paused = true,
+            PauseScope::Dao { dao_contract } => { self.paused_daos.insert(dao_contract); }
+            PauseScope::Drop { drop_id } => { self.paused_drops.insert(&drop_id.0); }
+        }
+        DaoBotEvent::Paused { scope, by: env::predecessor_account_id() }.emit();
+    }
+
+    /// Lift a pause set by `pause`. Callable by a pauser.
+    #[payable]
+    pub fn unpause(&mut self, scope: PauseScope) {
+        assert_one_yocto();
+        self.assert_role(AdminRole::Pauser);
+        match &scope {
+            PauseScope::Global => self.paused = false,
+            PauseScope::Dao { dao_contract } => { self.paused_daos.remove(dao_contract); }
+            PauseScope::Drop { drop_id } => { self.paused_drops.remove(&drop_id.0); }
+        }
+        DaoBotEvent::Unpaused { scope, by: env::predecessor_account_id() }.emit();
+    }
+
+    /// Whether claims of `drop_id` onboarding into `dao_contract` are currently rejected.
+    pub fn is_paused(&self, dao_contract: AccountId, drop_id: U128) -> bool {
+        self.internal_is_paused(&dao_contract, drop_id.0)
+    }
+
+    pub fn view_pause_state(&self) -> PauseState {
+        PauseState {
+            global: self.paused,
+            daos: self.paused_daos.to_vec(),
+            drops: self.paused_drops.iter().map(U128).collect(),
+        }
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_is_paused(&self, dao_contract: &AccountId, drop_id: DropId) -> bool {
+        self.paused || self.paused_daos.contains(dao_contract) || self.paused_drops.contains(&drop_id)
+    }
+
+    /// Whether a pause set since `registration_id` started now applies to it.
+    pub(crate) fn internal_registration_paused(&self, registration_id: u64) -> bool {
+        self.registrations.get(&registration_id)
+            .is_some_and(|registration| self.internal_is_paused(&registration.dao_contract, registration.drop_id.0))
+    }
+}
@@ -0,0 +1,114 @@
+use crate::*;
+
+/// Party that receives the deposit of a failed registration.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefundReceiver {
+    /// The drop funder, who ultimately paid for the claim.
+    Funder,
+    /// The Keypom contract that attached the deposit.
+    Keypom,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Credit the attached deposit to `account_id`, the caller by default. Funders can prepay claim
+    /// storage this way, and anyone can fund the treasury by crediting the bot account.
+    #[payable]
+    pub fn deposit(&mut self, account_id: Option<AccountId>) {
+        let account_id = account_id.unwrap_or_else(env::predecessor_account_id);
+        let amount = env::attached_deposit();
+        require!(amount > 0, "ATTACH A DEPOSIT TO CREDIT");
+        let balance = self.balances.get(&account_id).unwrap_or(0);
+        self.internal_set_balance(&account_id, balance + amount);
+    }
+
+    /// Withdraw the caller's credited balance, or `amount` of it.
+    pub fn withdraw(&mut self, amount: Option<U128>) -> Promise {
+        let account_id = env::predecessor_account_id();
+        let balance = self.balances.get(&account_id).unwrap_or(0);
+        let amount = amount.map(|a| a.0).unwrap_or(balance);
+        require!(amount > 0 && amount <= balance, "NOT ENOUGH BALANCE TO WITHDRAW");
+
+        self.internal_set_balance(&account_id, balance - amount);
+        self.internal_transfer(account_id, amount)
+    }
+
+    /// Send funds no funder owns, such as legacy bonds and refunds Keypom could not receive,
+    /// to `receiver_id`. They are credited to the bot's own balance. Callable by a treasurer.
+    #[payable]
+    pub fn withdraw_treasury(&mut self, receiver_id: AccountId, amount: Option<U128>) -> Promise {
+        assert_one_yocto();
+        self.assert_role(AdminRole::Treasurer);
+        let treasury = env::current_account_id();
+        let balance = self.balances.get(&treasury).unwrap_or(0);
+        let amount = amount.map(|a| a.0).unwrap_or(balance);
+        require!(amount > 0 && amount <= balance, "NOT ENOUGH TREASURY BALANCE TO WITHDRAW");
+
+        self.internal_set_balance(&treasury, balance - amount);
+        self.internal_transfer(receiver_id, amount)
+    }
+
+    pub fn view_balance(&self, account_id: AccountId) -> U128 {
+        U128(self.balances.get(&account_id).unwrap_or(0))
+    }
+
+    /// Credit the receiver's balance if a refund or withdrawal could not be delivered.
+    /// Keypom can't withdraw, so its undelivered refunds go to the treasury. Transfers to the bot
+    /// itself, such as refunds of legacy claims, never leave it and are credited to the treasury too.
+    #[private]
+    pub fn on_transfer_complete(&mut self, receiver_id: AccountId, amount: U128) {
+        let delivered = matches!(env::promise_result(0), PromiseResult::Successful(_));
+        if delivered && receiver_id != env::current_account_id() {
+            return;
+        }
+        let owner_id = if receiver_id == self.config.keypom_contract { env::current_account_id() } else { receiver_id };
+        log!("Transfer of {} not delivered, crediting {}", amount.0, owner_id);
+        let balance = self.balances.get(&owner_id).unwrap_or(0);
+        self.internal_set_balance(&owner_id, balance + amount.0);
+    }
+}
+
+impl Contract {
+    /// Abort a registration and send its deposit back to the configured refund receiver.
+    pub(crate) fn internal_fail_registration(&mut self, registration_id: u64, funder: &AccountId, deposit: Balance, reason: &str) -> Promise {
+        DaoBotEvent::RegistrationFailed {
+            registration_id,
+            reason: reason.to_string(),
+            refund_receiver: Some(self.internal_refund_receiver(funder)),
+            refund: U128(deposit),
+        }.emit();
+        self.internal_update_registration(registration_id, RegistrationStatus::Failed, None, Some(reason));
+        self.internal_refund_deposit(funder, deposit)
+    }
+
+    /// Send an unused registration deposit back to the configured refund receiver.
+    pub(crate) fn internal_refund_deposit(&mut self, funder: &AccountId, deposit: Balance) -> Promise {
+        let receiver_id = self.internal_refund_receiver(funder);
+        self.internal_transfer(receiver_id, deposit)
+    }
+
+    fn internal_refund_receiver(&self, funder: &AccountId) -> AccountId {
+        match self.config.refund_receiver {
+            RefundReceiver::Funder => funder.clone(),
+            RefundReceiver::Keypom => self.config.keypom_contract.clone(),
+        }
+    }
+
+    /// Push `amount` to `receiver_id`, falling back to crediting their balance if it bounces.
+    pub(crate) fn internal_transfer(&mut self, receiver_id: AccountId, amount: Balance) -> Promise {
+        Promise::new(receiver_id.clone())
+            .transfer(amount)
+            .then(
+                Self::ext(env::current_account_id())
+                .on_transfer_complete(receiver_id, U128(amount))
+            )
+    }
+
+    pub(crate) fn internal_set_balance(&mut self, account_id: &AccountId, balance: Balance) {
+        if balance == 0 {
+            self.balances.remove(account_id);
+        } else {
+            self.balances.insert(account_id, &balance);
+        }
+    }
+}
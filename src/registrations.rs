@@ -0,0 +1,233 @@
+use crate::*;
+
+/// Storage reserved for what a registration adds after it is created: its proposal id, failure reason,
+/// bond record and pending entry.
+pub const REGISTRATION_UPDATE_STORAGE: u64 = 512;
+
+/// Stage an onboarding attempt has reached.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegistrationStatus {
+    Started,
+    HumanCheckPassed,
+    CouncilCheckPassed,
+    ProposalAdded,
+    /// The bot voted but the proposal still needs votes from the council.
+    AwaitingVotes,
+    ProposalApproved,
+    /// The member was already in the target role, no proposal was needed.
+    AlreadyMember,
+    Failed,
+}
+
+/// One onboarding attempt, identified by the `registration_id` carried by its events.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone)]
+pub struct Registration {
+    pub id: u64,
+    pub dao_contract: AccountId,
+    /// Claiming account, for proposal kinds that carry one.
+    pub member_id: Option<AccountId>,
+    /// Target role, for proposal kinds that carry one.
+    pub role: Option<String>,
+    pub funder: AccountId,
+    pub drop_id: U128,
+    /// Set once the DAO has accepted the proposal.
+    pub proposal_id: Option<u64>,
+    /// Whether the bot's vote was predicted to pass the proposal on its own.
+    pub vote_decisive: Option<bool>,
+    /// Last status of the proposal read from the DAO.
+    pub proposal_status: Option<ProposalStatus>,
+    pub status: RegistrationStatus,
+    pub failure_reason: Option<String>,
+    /// Unix epoch in milliseconds.
+    pub created_at: u64,
+    /// Unix epoch in milliseconds.
+    pub updated_at: u64,
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn get_registration(&self, registration_id: u64) -> Option<Registration> {
+        self.registrations.get(&registration_id)
+    }
+
+    /// Registrations whose proposal is still waiting on votes from the DAO council.
+    pub fn get_pending_proposals(&self, from_index: Option<U64>, limit: Option<u64>) -> Vec<Registration> {
+        let start = from_index.map(|i| i.0).unwrap_or(0);
+        let limit = limit.unwrap_or(50);
+        self.pending_registrations.as_vector()
+            .iter()
+            .skip(start as usize)
+            .take(limit as usize)
+            .filter_map(|id| self.registrations.get(&id))
+            .collect()
+    }
+
+    /// Read the current status of a pending registration's proposal from its DAO. Can be called by anyone.
+    pub fn refresh_proposal_status(&mut self, registration_id: u64) -> Promise {
+        require!(self.pending_registrations.contains(&registration_id), "REGISTRATION IS NOT AWAITING VOTES");
+        let registration = self.registrations.get(&registration_id).unwrap_or_else(|| env::panic_str("REGISTRATION NOT FOUND"));
+        let proposal_id = registration.proposal_id.unwrap_or_else(|| env::panic_str("REGISTRATION HAS NO PROPOSAL"));
+        self.internal_fetch_proposal_status(registration_id, registration.dao_contract, proposal_id)
+    }
+
+    #[private]
+    pub fn callback_proposal_status(&mut self, registration_id: u64, dao_contract: AccountId, proposal_id: u64) -> Option<ProposalStatus> {
+        let proposal = match env::promise_result(0) {
+            PromiseResult::Successful(val) => near_sdk::serde_json::from_slice::<ProposalOutput>(&val).ok(),
+            _ => None,
+        };
+        let status = match proposal {
+            Some(proposal) => proposal.status,
+            None => {
+                // Leave the registration as is, the status can be refreshed later
+                if let Some(registration) = self.registrations.get(&registration_id) {
+                    if registration.status == RegistrationStatus::ProposalAdded {
+                        self.pending_registrations.insert(&registration_id);
+                        self.internal_update_registration(registration_id, RegistrationStatus::AwaitingVotes, None, None);
+                    }
+                }
+                return None;
+            }
+        };
+
+        if let Some(mut registration) = self.registrations.get(&registration_id) {
+            registration.proposal_status = Some(status);
+            self.registrations.insert(&registration_id, &registration);
+        }
+        self.internal_settle_bond(&dao_contract, proposal_id, status);
+
+        match status {
+            ProposalStatus::InProgress => {
+                self.pending_registrations.insert(&registration_id);
+                self.internal_update_registration(registration_id, RegistrationStatus::AwaitingVotes, None, None);
+            }
+            ProposalStatus::Approved => {
+                self.pending_registrations.remove(&registration_id);
+                DaoBotEvent::ProposalApproved { registration_id, dao_contract, proposal_id }.emit();
+                self.internal_update_registration(registration_id, RegistrationStatus::ProposalApproved, None, None);
+            }
+            _ => {
+                self.pending_registrations.remove(&registration_id);
+                let reason = format!("PROPOSAL {:?}", status).to_uppercase();
+                DaoBotEvent::RegistrationFailed { registration_id, reason: reason.clone(), refund_receiver: None, refund: U128(0) }.emit();
+                self.internal_update_registration(registration_id, RegistrationStatus::Failed, None, Some(&reason));
+            }
+        }
+        Some(status)
+    }
+
+    pub fn get_registrations_for_dao(&self, dao_contract: AccountId, from_index: Option<U64>, limit: Option<u64>) -> Vec<Registration> {
+        self.internal_paginate_registrations(self.registrations_by_dao.get(&dao_contract), from_index, limit)
+    }
+
+    pub fn get_registrations_for_member(&self, member_id: AccountId, from_index: Option<U64>, limit: Option<u64>) -> Vec<Registration> {
+        self.internal_paginate_registrations(self.registrations_by_member.get(&member_id), from_index, limit)
+    }
+
+    pub fn get_registrations_by_funder(&self, funder: AccountId, from_index: Option<U64>, limit: Option<u64>) -> Vec<Registration> {
+        self.internal_paginate_registrations(self.registrations_by_funder.get(&funder), from_index, limit)
+    }
+}
+
+impl Contract {
+    /// Record a new registration, paying for its storage out of `deposit`, or the funder's credited
+    /// balance if it falls short, and return its id along with what is left of the deposit.
+    /// Claims pay for the history they add so it never eats into the balances held by the bot.
+    pub(crate) fn internal_create_registration(&mut self, dao_contract: &AccountId, funder: &AccountId, drop_id: U128, kind: &ProposalKind, deposit: Balance) -> (u64, Balance) {
+        let initial_storage = env::storage_usage();
+        let id = self.internal_record_registration(dao_contract, funder, drop_id, kind);
+
+        let bytes = env::storage_usage() - initial_storage + REGISTRATION_UPDATE_STORAGE;
+        let cost = Balance::from(bytes) * env::storage_byte_cost();
+        let deposit = self.internal_rollover_deposit(funder, deposit, cost)
+            .unwrap_or_else(|| env::panic_str(&format!("ATTACH AT LEAST {} YOCTO TO COVER REGISTRATION STORAGE", cost)));
+        (id, deposit - cost)
+    }
+
+    fn internal_record_registration(&mut self, dao_contract: &AccountId, funder: &AccountId, drop_id: U128, kind: &ProposalKind) -> u64 {
+        let id = self.next_registration_id;
+        self.next_registration_id += 1;
+
+        let now = env::block_timestamp_ms();
+        let registration = Registration {
+            id,
+            dao_contract: dao_contract.clone(),
+            member_id: kind.claimer().cloned(),
+            role: kind.role().map(|r| r.to_string()),
+            funder: funder.clone(),
+            drop_id,
+            proposal_id: None,
+            vote_decisive: None,
+            proposal_status: None,
+            status: RegistrationStatus::Started,
+            failure_reason: None,
+            created_at: now,
+            updated_at: now,
+        };
+        self.registrations.insert(&id, &registration);
+
+        Self::internal_index_registration(&mut self.registrations_by_dao, StorageKey::RegistrationsByDaoInner, dao_contract, id);
+        Self::internal_index_registration(&mut self.registrations_by_funder, StorageKey::RegistrationsByFunderInner, funder, id);
+        if let Some(member_id) = &registration.member_id {
+            Self::internal_index_registration(&mut self.registrations_by_member, StorageKey::RegistrationsByMemberInner, member_id, id);
+        }
+        id
+    }
+
+    /// Move a registration to `status`, recording the proposal id or failure reason if given.
+    pub(crate) fn internal_update_registration(&mut self, registration_id: u64, status: RegistrationStatus, proposal_id: Option<u64>, failure_reason: Option<&str>) {
+        if let Some(mut registration) = self.registrations.get(&registration_id) {
+            registration.status = status;
+            registration.proposal_id = proposal_id.or(registration.proposal_id);
+            registration.failure_reason = failure_reason.map(|r| r.to_string());
+            registration.updated_at = env::block_timestamp_ms();
+            self.registrations.insert(&registration_id, &registration);
+        }
+    }
+
+    pub(crate) fn internal_set_registration_role(&mut self, registration_id: u64, role: &str) {
+        if let Some(mut registration) = self.registrations.get(&registration_id) {
+            registration.role = Some(role.to_string());
+            self.registrations.insert(&registration_id, &registration);
+        }
+    }
+
+    pub(crate) fn internal_record_vote_prediction(&mut self, registration_id: u64, vote_decisive: bool) {
+        if let Some(mut registration) = self.registrations.get(&registration_id) {
+            registration.vote_decisive = Some(vote_decisive);
+            self.registrations.insert(&registration_id, &registration);
+        }
+    }
+
+    pub(crate) fn internal_fetch_proposal_status(&self, registration_id: u64, dao_contract: AccountId, proposal_id: u64) -> Promise {
+        ext_dao::ext(dao_contract.clone())
+        .with_static_gas(self.config.view_call_gas)
+        .get_proposal(proposal_id)
+        .then(
+            Self::ext(env::current_account_id())
+            .callback_proposal_status(registration_id, dao_contract, proposal_id)
+        )
+    }
+
+    fn internal_index_registration(index: &mut LookupMap<AccountId, Vector<u64>>, prefix: fn(CryptoHash) -> StorageKey, account_id: &AccountId, id: u64) {
+        let mut ids = index.get(account_id).unwrap_or_else(|| {
+            Vector::new(prefix(env::sha256_array(account_id.as_bytes())))
+        });
+        ids.push(&id);
+        index.insert(account_id, &ids);
+    }
+
+    fn internal_paginate_registrations(&self, ids: Option<Vector<u64>>, from_index: Option<U64>, limit: Option<u64>) -> Vec<Registration> {
+        let ids = match ids {
+            Some(ids) => ids,
+            None => return vec![],
+        };
+        let start = from_index.map(|i| i.0).unwrap_or(0);
+        let limit = limit.unwrap_or(50);
+
+        (start..std::cmp::min(start.saturating_add(limit), ids.len()))
+            .filter_map(|index| ids.get(index))
+            .filter_map(|id| self.registrations.get(&id))
+            .collect()
+    }
+}
@@ -0,0 +1,163 @@
+use crate::*;
+use near_sdk::serde::de::DeserializeOwned;
+
+/// Tokens returned by `sbt_tokens_by_owner`, grouped by issuer.
+pub type TokensByIssuer = Vec<(AccountId, Vec<OwnedToken>)>;
+
+/// Proof-of-Humanity requirement evaluated against `sbt_tokens_by_owner`.
+/// The default requirement accepts any valid token from any issuer.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone, Default)]
+pub struct HumanRequirement {
+    /// SBT registry to query, the bot config's `sbt_registry` if None. Only DAO configs may set it.
+    pub registry: Option<AccountId>,
+    /// Issuers whose tokens count. Empty accepts any issuer.
+    pub issuers: Vec<AccountId>,
+    /// Classes the claiming account must hold a token of, one token per class. Empty accepts any class.
+    pub classes: Vec<ClassId>,
+    /// Minimum number of days since a token was issued for it to count, against sybil farming.
+    /// Tokens without `issued_at` never count when set.
+    pub min_token_age_days: Option<u32>,
+}
+
+/// Token that satisfied a human requirement.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SatisfyingToken {
+    pub issuer: AccountId,
+    pub token: TokenId,
+    pub class: ClassId,
+}
+
+const MS_PER_DAY: u64 = 24 * 60 * 60 * 1000;
+
+impl HumanRequirement {
+    /// Issuer to filter `sbt_tokens_by_owner` with, when the allowlist has a single entry.
+    pub fn issuer_filter(&self) -> Option<AccountId> {
+        match self.issuers.as_slice() {
+            [issuer] => Some(issuer.clone()),
+            _ => None,
+        }
+    }
+
+    /// Whether `metadata` is unexpired and old enough at `now_ms`.
+    pub fn is_token_valid(&self, metadata: &TokenMetadata, now_ms: u64) -> bool {
+        let unexpired = metadata.expires_at.is_none_or(|expires_at| expires_at > now_ms);
+        let old_enough = match self.min_token_age_days {
+            Some(days) => metadata.issued_at
+                .is_some_and(|issued_at| issued_at.saturating_add(u64::from(days) * MS_PER_DAY) <= now_ms),
+            None => true,
+        };
+        unexpired && old_enough
+    }
+
+    /// Tokens, out of those returned by `sbt_tokens_by_owner`, that satisfy this requirement at `now_ms`:
+    /// one per required class, or any one if no class is required. None if the requirement isn't met.
+    pub fn satisfying_tokens(&self, tokens: &[(AccountId, Vec<OwnedToken>)], now_ms: u64) -> Option<Vec<SatisfyingToken>> {
+        let accepted = tokens.iter()
+            .filter(|(issuer, _)| self.issuers.is_empty() || self.issuers.contains(issuer))
+            .flat_map(|(issuer, tokens)| tokens.iter().map(move |token| (issuer, token)))
+            .filter(|(_, token)| self.is_token_valid(&token.metadata, now_ms))
+            .map(|(issuer, token)| SatisfyingToken { issuer: issuer.clone(), token: token.token, class: token.metadata.class })
+            .collect::<Vec<SatisfyingToken>>();
+
+        if self.classes.is_empty() {
+            return accepted.into_iter().next().map(|token| vec![token]);
+        }
+        self.classes.iter()
+            .map(|class| accepted.iter().find(|token| token.class == *class).cloned())
+            .collect()
+    }
+}
+
+/// Token checks run on the claiming account, passed along to the human check callback.
+/// The humanity check, either the registry's plain `is_human` proof or the configured requirements,
+/// is kept apart from role mappings, which only pick the role once the claimer passed it.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SbtCheck {
+    /// Whether the registry's `is_human` proof is required, used when no requirement is configured.
+    pub is_human: bool,
+    /// Requirement from the DAO config, the only one that may pick the registry.
+    pub dao_requirement: Option<HumanRequirement>,
+    /// Requirement from the drop config, checked on top of the DAO's.
+    pub drop_requirement: Option<HumanRequirement>,
+    pub role_mappings: Vec<RoleMapping>,
+}
+
+impl SbtCheck {
+    /// Configured requirements, all of which must be met.
+    pub fn requirements(&self) -> impl Iterator<Item = &HumanRequirement> {
+        self.dao_requirement.iter().chain(self.drop_requirement.iter())
+    }
+
+    /// Whether the claimer's tokens must be fetched with `sbt_tokens_by_owner`.
+    pub fn fetches_tokens(&self) -> bool {
+        self.requirements().next().is_some() || !self.role_mappings.is_empty()
+    }
+
+    /// Issuer to filter the fetched tokens by, if every requirement allows a single, shared issuer.
+    /// Role mappings may match tokens of any issuer.
+    pub fn issuer_filter(&self) -> Option<AccountId> {
+        if !self.role_mappings.is_empty() {
+            return None;
+        }
+        let mut filters = self.requirements().map(HumanRequirement::issuer_filter);
+        let issuer = filters.next().flatten()?;
+        filters.all(|filter| filter.as_ref() == Some(&issuer)).then_some(issuer)
+    }
+
+    /// Check the results of the registry calls: the `is_human` proof first if required, then the
+    /// claimer's tokens if fetched. Returns the tokens satisfying the requirements and all fetched tokens.
+    pub fn evaluate(&self, now_ms: u64) -> Result<(Vec<SatisfyingToken>, TokensByIssuer), &'static str> {
+        if self.is_human {
+            let proof: Vec<(AccountId, Vec<ClassId>)> = promise_json(0)?;
+            if proof.is_empty() {
+                return Err("CLAIMING ACCOUNT MUST BE HUMAN");
+            }
+        }
+        if !self.fetches_tokens() {
+            return Ok((vec![], vec![]));
+        }
+
+        // Expired or too recent tokens don't count towards a configured requirement
+        let tokens: TokensByIssuer = promise_json(u64::from(self.is_human))?;
+        let satisfying = self.satisfying_tokens(&tokens, now_ms).ok_or("CLAIMING ACCOUNT DOES NOT HOLD VALID REQUIRED SBTS")?;
+        Ok((satisfying, tokens))
+    }
+
+    /// Tokens satisfying every configured requirement at `now_ms`, None if any of them isn't met.
+    pub fn satisfying_tokens(&self, tokens: &[(AccountId, Vec<OwnedToken>)], now_ms: u64) -> Option<Vec<SatisfyingToken>> {
+        self.requirements().try_fold(vec![], |mut satisfying, requirement| {
+            satisfying.extend(requirement.satisfying_tokens(tokens, now_ms)?);
+            Some(satisfying)
+        })
+    }
+}
+
+/// Entry of a DAO's role mapping table: claimers holding a matching token join `role`.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone)]
+pub struct RoleMapping {
+    /// Issuer the token must come from, any issuer if None.
+    pub issuer: Option<AccountId>,
+    /// Class the token must have, any class if None.
+    pub class: Option<ClassId>,
+    pub role: String,
+}
+
+/// Role of the first mapping, in table order, matched by one of `tokens` that `is_valid`,
+/// along with the token that matched it.
+pub fn resolve_role(mappings: &[RoleMapping], tokens: &[(AccountId, Vec<OwnedToken>)], is_valid: impl Fn(&TokenMetadata) -> bool) -> Option<(String, SatisfyingToken)> {
+    mappings.iter().find_map(|mapping| {
+        tokens.iter()
+            .filter(|(issuer, _)| mapping.issuer.as_ref().is_none_or(|i| i == issuer))
+            .flat_map(|(issuer, tokens)| tokens.iter().map(move |token| (issuer, token)))
+            .find(|(_, token)| mapping.class.is_none_or(|c| c == token.metadata.class) && is_valid(&token.metadata))
+            .map(|(issuer, token)| (mapping.role.clone(), SatisfyingToken { issuer: issuer.clone(), token: token.token, class: token.metadata.class }))
+    })
+}
+
+/// Parse the JSON result of the promise at `index`.
+fn promise_json<T: DeserializeOwned>(index: u64) -> Result<T, &'static str> {
+    match env::promise_result(index) {
+        PromiseResult::Successful(val) => near_sdk::serde_json::from_slice(&val).map_err(|_| "ERR_WRONG_VAL_RECEIVED"),
+        _ => Err("PROBLEM WITH PROMISE"),
+    }
+}
@@ -0,0 +1,756 @@
+use crate::*;
+use near_sdk::serde_json::{self, json};
+use near_sdk::mock::VmAction;
+use near_sdk::test_utils::{get_created_receipts, get_logs, VMContextBuilder};
+use near_sdk::{testing_env, RuntimeFeesConfig, VMConfig, ONE_NEAR};
+use std::collections::HashMap;
+
+const NOW_MS: u64 = 1_700_000_000_000;
+const BOND: Balance = ONE_NEAR / 10;
+const DROP_ID: u128 = 42;
+
+fn bot() -> AccountId { "bot.near".parse().unwrap() }
+fn owner() -> AccountId { "owner.near".parse().unwrap() }
+fn keypom() -> AccountId { "v2.keypom.near".parse().unwrap() }
+fn registry() -> AccountId { "registry.i-am-human.near".parse().unwrap() }
+fn fractal() -> AccountId { "fractal.i-am-human.near".parse().unwrap() }
+fn dao() -> AccountId { "dao.sputnik-dao.near".parse().unwrap() }
+fn funder() -> AccountId { "funder.near".parse().unwrap() }
+fn member() -> AccountId { "member.near".parse().unwrap() }
+
+fn config() -> ContractConfig {
+    ContractConfig {
+        keypom_contract: keypom(),
+        sbt_registry: registry(),
+        council_role: "council".to_string(),
+        view_call_gas: Gas(0),
+        proposal_call_gas: Gas(0),
+        refund_receiver: RefundReceiver::Funder,
+        flow: RegistrationFlow::V2,
+        accept_legacy_calls: false,
+    }
+}
+
+fn context() -> VMContextBuilder {
+    let mut builder = VMContextBuilder::new();
+    builder
+        .current_account_id(bot())
+        .predecessor_account_id(bot())
+        .account_balance(100 * ONE_NEAR)
+        .block_timestamp(NOW_MS * 1_000_000);
+    builder
+}
+
+/// Mock the result of the promise a callback is chained on.
+fn set_promise_result(result: PromiseResult) {
+    testing_env!(context().build(), VMConfig::test(), RuntimeFeesConfig::test(), Default::default(), vec![result]);
+}
+
+fn set_promise_json(value: near_sdk::serde_json::Value) {
+    set_promise_result(PromiseResult::Successful(value.to_string().into_bytes()));
+}
+
+/// Mock the results of joint promises a callback is chained on.
+fn set_promise_jsons(values: Vec<near_sdk::serde_json::Value>) {
+    let results = values.into_iter().map(|value| PromiseResult::Successful(value.to_string().into_bytes())).collect();
+    testing_env!(context().build(), VMConfig::test(), RuntimeFeesConfig::test(), Default::default(), results);
+}
+
+fn setup() -> Contract {
+    testing_env!(context().build());
+    let mut contract = Contract::new(owner(), Some(config()));
+    contract.drop_configs.insert(&(funder(), DROP_ID), &DropRecord {
+        funder: funder(),
+        config: DropConfig {
+            dao_contract: dao(),
+            allowed_roles: vec![],
+            human_only: false,
+            human_requirement: None,
+            max_registrations: None,
+            expires_at: None,
+        },
+        registrations: 0,
+    });
+    contract
+}
+
+fn proposal(role: &str) -> ProposalInput {
+    ProposalInput {
+        description: "Auto-registration".to_string(),
+        kind: ProposalKind::AddMemberToRole { member_id: member(), role: role.to_string() },
+    }
+}
+
+/// Arguments Keypom sends to `new_auto_registration`.
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+struct NewAutoRegistrationArgs {
+    dao_contract: AccountId,
+    keypom_args: Option<KeypomArgs>,
+    funder: Option<AccountId>,
+    proposal: ProposalInput,
+}
+
+fn start_registration(contract: &mut Contract, role: &str) -> u64 {
+    contract.internal_create_registration(&dao(), &funder(), U128(DROP_ID), &proposal(role).kind, ONE_NEAR).0
+}
+
+fn group(name: &str, accounts: &[AccountId], permissions: &[&str]) -> RolePermission {
+    RolePermission {
+        name: name.to_string(),
+        kind: RoleKind::Group(accounts.iter().cloned().collect()),
+        permissions: permissions.iter().map(|p| p.to_string()).collect(),
+        vote_policy: HashMap::new(),
+    }
+}
+
+fn policy(roles: Vec<RolePermission>) -> Policy {
+    Policy {
+        roles,
+        default_vote_policy: VotePolicy { weight_kind: WeightKind::RoleWeight, quorum: U128(0), threshold: WeightOrRatio::Ratio(1, 2) },
+        proposal_bond: U128(BOND),
+        proposal_period: U64(7 * 24 * 3_600_000_000_000),
+        bounty_bond: U128(BOND),
+        bounty_forgiveness_period: U64(24 * 3_600_000_000_000),
+    }
+}
+
+/// Council with the funder and the bot, and an empty `member` role.
+fn default_policy() -> Policy {
+    policy(vec![
+        group("council", &[funder(), bot()], &["*:*"]),
+        group("member", &[], &[]),
+    ])
+}
+
+fn token(token: TokenId, class: ClassId, issued_at: Option<u64>, expires_at: Option<u64>) -> OwnedToken {
+    OwnedToken {
+        token,
+        metadata: TokenMetadata { class, issued_at, expires_at, reference: None, reference_hash: None },
+    }
+}
+
+fn events() -> Vec<near_sdk::serde_json::Value> {
+    get_logs()
+        .iter()
+        .filter_map(|log| log.strip_prefix("EVENT_JSON:"))
+        .map(|event| serde_json::from_str(event).unwrap())
+        .collect()
+}
+
+fn event_names() -> Vec<String> {
+    events().iter().map(|event| event["event"].as_str().unwrap().to_string()).collect()
+}
+
+fn registration(contract: &Contract, registration_id: u64) -> Registration {
+    contract.get_registration(registration_id).unwrap()
+}
+
+fn assert_failed(contract: &Contract, registration_id: u64, reason: &str) {
+    let registration = registration(contract, registration_id);
+    assert_eq!(registration.status, RegistrationStatus::Failed);
+    assert_eq!(registration.failure_reason.as_deref(), Some(reason));
+}
+
+// internal_human_check
+
+#[test]
+fn human_check_passes_with_is_human_proof() {
+    let mut contract = setup();
+    let id = start_registration(&mut contract, "member");
+
+    set_promise_json(json!([[fractal(), [1]]]));
+    contract.internal_human_check(id, funder(), proposal("member"), dao(), U128(BOND), SbtCheck { is_human: true, dao_requirement: None, drop_requirement: None, role_mappings: vec![] });
+
+    assert_eq!(event_names(), vec!["human_check_passed"]);
+    assert_eq!(registration(&contract, id).status, RegistrationStatus::HumanCheckPassed);
+}
+
+#[test]
+fn human_check_fails_without_is_human_proof() {
+    let mut contract = setup();
+    let id = start_registration(&mut contract, "member");
+
+    set_promise_json(json!([]));
+    contract.internal_human_check(id, funder(), proposal("member"), dao(), U128(BOND), SbtCheck { is_human: true, dao_requirement: None, drop_requirement: None, role_mappings: vec![] });
+
+    assert_eq!(event_names(), vec!["human_check_failed", "registration_failed"]);
+    assert_failed(&contract, id, "CLAIMING ACCOUNT MUST BE HUMAN");
+    assert_eq!(events()[1]["data"][0]["refund"], json!(BOND.to_string()));
+}
+
+#[test]
+fn human_check_rejects_malformed_json() {
+    let mut contract = setup();
+    let id = start_registration(&mut contract, "member");
+
+    set_promise_result(PromiseResult::Successful(b"{not json".to_vec()));
+    contract.internal_human_check(id, funder(), proposal("member"), dao(), U128(BOND), SbtCheck { is_human: true, dao_requirement: None, drop_requirement: None, role_mappings: vec![] });
+
+    assert_failed(&contract, id, "ERR_WRONG_VAL_RECEIVED");
+}
+
+#[test]
+fn human_check_fails_when_registry_call_fails() {
+    let mut contract = setup();
+    let id = start_registration(&mut contract, "member");
+
+    set_promise_result(PromiseResult::Failed);
+    contract.internal_human_check(id, funder(), proposal("member"), dao(), U128(BOND), SbtCheck { is_human: true, dao_requirement: None, drop_requirement: None, role_mappings: vec![] });
+
+    assert_eq!(event_names(), vec!["human_check_failed", "registration_failed"]);
+    assert_failed(&contract, id, "PROBLEM WITH PROMISE");
+}
+
+#[test]
+fn proposal_callback_credits_rolled_over_bond_back_to_funder() {
+    let mut contract = setup();
+    contract.config.refund_receiver = RefundReceiver::Keypom;
+    let id = start_registration(&mut contract, "member");
+
+    // A quarter of the bond came from the funder's balance, only the rest goes to Keypom
+    set_promise_result(PromiseResult::Failed);
+    contract.callback_new_auto_registration(id, funder(), dao(), U128(BOND), U128(BOND / 4));
+
+    assert_eq!(contract.balances.get(&funder()), Some(BOND / 4));
+    let event = &events()[0]["data"][0];
+    assert_eq!(event["refund"], json!((BOND - BOND / 4).to_string()));
+    assert_eq!(event["refund_receiver"], json!(keypom()));
+}
+
+#[test]
+fn human_check_ignores_expired_and_recent_tokens() {
+    let mut contract = setup();
+    let id = start_registration(&mut contract, "member");
+    let requirement = HumanRequirement {
+        registry: None,
+        issuers: vec![fractal()],
+        classes: vec![1],
+        min_token_age_days: Some(30),
+    };
+
+    // One token expired yesterday, the other was issued a day ago
+    let day = 24 * 60 * 60 * 1000;
+    set_promise_json(json!([[fractal(), [
+        token(1, 1, Some(NOW_MS - 60 * day), Some(NOW_MS - day)),
+        token(2, 1, Some(NOW_MS - day), None),
+    ]]]));
+    contract.internal_human_check(id, funder(), proposal("member"), dao(), U128(BOND), SbtCheck { is_human: false, dao_requirement: Some(requirement.clone()), drop_requirement: None, role_mappings: vec![] });
+    assert_failed(&contract, id, "CLAIMING ACCOUNT DOES NOT HOLD VALID REQUIRED SBTS");
+
+    let id = start_registration(&mut contract, "member");
+    set_promise_json(json!([[fractal(), [token(3, 1, Some(NOW_MS - 31 * day), None)]]]));
+    contract.internal_human_check(id, funder(), proposal("member"), dao(), U128(BOND), SbtCheck { is_human: false, dao_requirement: Some(requirement), drop_requirement: None, role_mappings: vec![] });
+    assert_eq!(registration(&contract, id).status, RegistrationStatus::HumanCheckPassed);
+    assert_eq!(events()[0]["data"][0]["tokens"][0]["token"], json!(3));
+}
+
+#[test]
+fn human_check_resolves_role_from_mapping() {
+    let mut contract = setup();
+    let id = start_registration(&mut contract, "member");
+    let role_mappings = vec![
+        RoleMapping { issuer: Some(fractal()), class: Some(2), role: "contributor".to_string() },
+        RoleMapping { issuer: None, class: None, role: "member".to_string() },
+    ];
+
+    set_promise_json(json!([[fractal(), [token(7, 2, Some(NOW_MS), None)]]]));
+    contract.internal_human_check(id, funder(), proposal("member"), dao(), U128(BOND), SbtCheck { is_human: false, dao_requirement: None, drop_requirement: None, role_mappings });
+
+    assert_eq!(event_names(), vec!["role_resolved"]);
+    assert_eq!(registration(&contract, id).role.as_deref(), Some("contributor"));
+}
+
+#[test]
+fn human_check_fails_when_no_role_mapping_matches() {
+    let mut contract = setup();
+    let id = start_registration(&mut contract, "member");
+    let role_mappings = vec![RoleMapping { issuer: Some(fractal()), class: Some(2), role: "contributor".to_string() }];
+
+    set_promise_json(json!([[fractal(), [token(7, 1, Some(NOW_MS), None)]]]));
+    contract.internal_human_check(id, funder(), proposal("member"), dao(), U128(BOND), SbtCheck { is_human: false, dao_requirement: None, drop_requirement: None, role_mappings });
+
+    assert_failed(&contract, id, "NO ROLE MAPPING MATCHES CLAIMING ACCOUNT'S SBTS");
+}
+
+#[test]
+fn human_check_keeps_is_human_proof_with_role_mappings() {
+    let mut contract = setup();
+    let role_mappings = vec![RoleMapping { issuer: None, class: None, role: "member".to_string() }];
+    let sbt_check = SbtCheck { is_human: true, dao_requirement: None, drop_requirement: None, role_mappings };
+
+    // A token matching a mapping is no proof of humanity
+    let id = start_registration(&mut contract, "member");
+    set_promise_jsons(vec![json!([]), json!([["badges.near", [token(7, 1, Some(NOW_MS), None)]]])]);
+    contract.internal_human_check(id, funder(), proposal("member"), dao(), U128(BOND), sbt_check.clone());
+    assert_eq!(event_names(), vec!["human_check_failed", "registration_failed"]);
+    assert_failed(&contract, id, "CLAIMING ACCOUNT MUST BE HUMAN");
+
+    let id = start_registration(&mut contract, "member");
+    set_promise_jsons(vec![json!([[fractal(), [1]]]), json!([["badges.near", [token(7, 1, Some(NOW_MS), None)]]])]);
+    contract.internal_human_check(id, funder(), proposal("member"), dao(), U128(BOND), sbt_check);
+    assert_eq!(event_names(), vec!["human_check_passed", "role_resolved"]);
+}
+
+#[test]
+fn human_check_requires_drop_and_dao_requirements() {
+    let mut contract = setup();
+    let id = start_registration(&mut contract, "member");
+    let dao_requirement = HumanRequirement { registry: None, issuers: vec![fractal()], classes: vec![1], min_token_age_days: None };
+    let drop_requirement = HumanRequirement { classes: vec![2], ..dao_requirement.clone() };
+
+    // Meeting the drop's requirement alone doesn't bypass the DAO's
+    set_promise_json(json!([[fractal(), [token(1, 2, Some(NOW_MS), None)]]]));
+    contract.internal_human_check(id, funder(), proposal("member"), dao(), U128(BOND), SbtCheck { is_human: false, dao_requirement: Some(dao_requirement.clone()), drop_requirement: Some(drop_requirement.clone()), role_mappings: vec![] });
+    assert_failed(&contract, id, "CLAIMING ACCOUNT DOES NOT HOLD VALID REQUIRED SBTS");
+
+    let id = start_registration(&mut contract, "member");
+    set_promise_json(json!([[fractal(), [token(1, 2, Some(NOW_MS), None), token(2, 1, Some(NOW_MS), None)]]]));
+    contract.internal_human_check(id, funder(), proposal("member"), dao(), U128(BOND), SbtCheck { is_human: false, dao_requirement: Some(dao_requirement), drop_requirement: Some(drop_requirement), role_mappings: vec![] });
+    assert_eq!(registration(&contract, id).status, RegistrationStatus::HumanCheckPassed);
+    assert_eq!(events()[0]["data"][0]["tokens"].as_array().unwrap().len(), 2);
+}
+
+// internal_get_roles_callback
+
+#[test]
+fn roles_callback_passes_council_funder() {
+    let mut contract = setup();
+    let id = start_registration(&mut contract, "member");
+
+    set_promise_json(json!(default_policy()));
+    contract.internal_get_roles_callback(id, funder(), proposal("member"), dao(), U128(BOND));
+
+    assert_eq!(event_names(), vec!["council_check_passed"]);
+    let registration = registration(&contract, id);
+    assert_eq!(registration.status, RegistrationStatus::CouncilCheckPassed);
+    assert_eq!(registration.vote_decisive, Some(false));
+}
+
+#[test]
+fn roles_callback_rejects_funder_outside_council() {
+    let mut contract = setup();
+    let id = start_registration(&mut contract, "member");
+
+    set_promise_json(json!(policy(vec![
+        group("council", &[bot()], &["*:*"]),
+        group("member", &[], &[]),
+    ])));
+    contract.internal_get_roles_callback(id, funder(), proposal("member"), dao(), U128(BOND));
+
+    assert_eq!(event_names(), vec!["council_check_failed", "registration_failed"]);
+    assert_failed(&contract, id, "FUNDER IS NOT COUNCIL");
+}
+
+#[test]
+fn roles_callback_rejects_dao_without_council() {
+    let mut contract = setup();
+    let id = start_registration(&mut contract, "member");
+
+    set_promise_json(json!(policy(vec![
+        group("admins", &[funder(), bot()], &["*:*"]),
+        group("member", &[], &[]),
+    ])));
+    contract.internal_get_roles_callback(id, funder(), proposal("member"), dao(), U128(BOND));
+
+    assert_failed(&contract, id, "DAO HAS NO COUNCIL ROLE");
+}
+
+#[test]
+fn roles_callback_rejects_malformed_json() {
+    let mut contract = setup();
+    let id = start_registration(&mut contract, "member");
+
+    set_promise_json(json!({"roles": "council"}));
+    contract.internal_get_roles_callback(id, funder(), proposal("member"), dao(), U128(BOND));
+
+    assert_eq!(event_names(), vec!["registration_failed"]);
+    assert_failed(&contract, id, "ERR_WRONG_VAL_RECEIVED");
+}
+
+#[test]
+fn roles_callback_fails_when_policy_call_fails() {
+    let mut contract = setup();
+    let id = start_registration(&mut contract, "member");
+
+    set_promise_result(PromiseResult::Failed);
+    contract.internal_get_roles_callback(id, funder(), proposal("member"), dao(), U128(BOND));
+
+    assert_failed(&contract, id, "PROBLEM WITH PROMISE");
+}
+
+#[test]
+fn roles_callback_rejects_bot_without_permissions() {
+    let mut contract = setup();
+    let id = start_registration(&mut contract, "member");
+
+    set_promise_json(json!(policy(vec![
+        group("council", &[funder(), bot()], &["add_member_to_role:AddProposal"]),
+        group("member", &[], &[]),
+    ])));
+    contract.internal_get_roles_callback(id, funder(), proposal("member"), dao(), U128(BOND));
+
+    assert_eq!(events()[0]["data"][0]["missing_permissions"], json!(["add_member_to_role:VoteApprove"]));
+    assert_failed(&contract, id, "BOT LACKS PERMISSIONS add_member_to_role:VoteApprove");
+}
+
+#[test]
+fn roles_callback_rejects_unknown_role() {
+    let mut contract = setup();
+    let id = start_registration(&mut contract, "ghost");
+
+    set_promise_json(json!(default_policy()));
+    contract.internal_get_roles_callback(id, funder(), proposal("ghost"), dao(), U128(BOND));
+
+    assert_eq!(event_names(), vec!["role_check_failed", "registration_failed"]);
+    assert_failed(&contract, id, "ROLE ghost DOES NOT EXIST ON DAO");
+}
+
+#[test]
+fn roles_callback_skips_existing_member() {
+    let mut contract = setup();
+    let id = start_registration(&mut contract, "member");
+
+    set_promise_json(json!(policy(vec![
+        group("council", &[funder(), bot()], &["*:*"]),
+        group("member", &[member()], &[]),
+    ])));
+    contract.internal_get_roles_callback(id, funder(), proposal("member"), dao(), U128(BOND));
+
+    assert_eq!(event_names(), vec!["council_check_passed", "already_member"]);
+    assert_eq!(registration(&contract, id).status, RegistrationStatus::AlreadyMember);
+}
+
+#[test]
+fn roles_callback_requires_deposit_covering_bond() {
+    let mut contract = setup();
+    let id = start_registration(&mut contract, "member");
+
+    set_promise_json(json!(default_policy()));
+    contract.internal_get_roles_callback(id, funder(), proposal("member"), dao(), U128(BOND - 1));
+
+    assert_failed(&contract, id, &format!("ATTACH MORE NEAR, AT LEAST {} YOCTO", BOND));
+}
+
+#[test]
+fn roles_callback_aborts_paused_registration() {
+    let mut contract = setup();
+    let id = start_registration(&mut contract, "member");
+    contract.paused_daos.insert(&dao());
+
+    set_promise_json(json!(default_policy()));
+    contract.internal_get_roles_callback(id, funder(), proposal("member"), dao(), U128(BOND));
+
+    assert_failed(&contract, id, "REGISTRATIONS PAUSED");
+}
+
+// callback_new_auto_registration
+
+#[test]
+fn proposal_callback_records_proposal_and_bond() {
+    let mut contract = setup();
+    let id = start_registration(&mut contract, "member");
+
+    set_promise_json(json!(7));
+    contract.callback_new_auto_registration(id, funder(), dao(), U128(BOND), U128(0));
+
+    assert_eq!(event_names(), vec!["proposal_added"]);
+    let registration = registration(&contract, id);
+    assert_eq!(registration.status, RegistrationStatus::ProposalAdded);
+    assert_eq!(registration.proposal_id, Some(7));
+    assert_eq!(contract.view_bond(dao(), 7).unwrap().funder, funder());
+}
+
+#[test]
+fn proposal_callback_rejects_malformed_json() {
+    let mut contract = setup();
+    let id = start_registration(&mut contract, "member");
+
+    set_promise_json(json!("seven"));
+    contract.callback_new_auto_registration(id, funder(), dao(), U128(BOND), U128(0));
+
+    // The bond is held by the DAO, nothing is refunded
+    assert_eq!(events()[0]["data"][0]["refund"], json!("0"));
+    assert_failed(&contract, id, "ERR_WRONG_VAL_RECEIVED");
+}
+
+#[test]
+fn proposal_callback_refunds_failed_proposal() {
+    let mut contract = setup();
+    let id = start_registration(&mut contract, "member");
+
+    set_promise_result(PromiseResult::Failed);
+    contract.callback_new_auto_registration(id, funder(), dao(), U128(BOND), U128(0));
+
+    let event = &events()[0]["data"][0];
+    assert_eq!(event["refund"], json!(BOND.to_string()));
+    assert_eq!(event["refund_receiver"], json!(funder()));
+    assert_failed(&contract, id, "PROBLEM WITH PROMISE");
+}
+
+// bonds
+
+const PROPOSAL_ID: u64 = 3;
+
+fn record_bond(contract: &mut Contract) {
+    contract.bonds.insert(&(dao(), PROPOSAL_ID), &BondRecord { funder: funder(), amount: U128(BOND) });
+}
+
+fn set_proposal_status(status: &str) {
+    set_promise_json(json!({"id": PROPOSAL_ID, "proposer": bot(), "status": status}));
+}
+
+fn assert_bond_credited_once(status: &str) {
+    let mut contract = setup();
+    record_bond(&mut contract);
+
+    set_proposal_status(status);
+    contract.on_claim_bond(dao(), PROPOSAL_ID);
+    assert_eq!(contract.balances.get(&funder()), Some(BOND));
+    assert!(contract.view_bond(dao(), PROPOSAL_ID).is_none());
+
+    // A repeated claim finds no record and credits nothing
+    set_proposal_status(status);
+    contract.on_claim_bond(dao(), PROPOSAL_ID);
+    assert_eq!(contract.balances.get(&funder()), Some(BOND));
+}
+
+#[test]
+fn approved_proposal_bonds_are_credited_once() {
+    assert_bond_credited_once("Approved");
+}
+
+#[test]
+fn expired_proposal_bonds_are_credited_once() {
+    assert_bond_credited_once("Expired");
+}
+
+#[test]
+fn removed_proposal_bonds_are_dropped_without_credit() {
+    let mut contract = setup();
+    record_bond(&mut contract);
+
+    set_proposal_status("Removed");
+    contract.on_claim_bond(dao(), PROPOSAL_ID);
+
+    assert!(contract.view_bond(dao(), PROPOSAL_ID).is_none());
+    assert_eq!(contract.balances.get(&funder()), None);
+}
+
+#[test]
+fn in_progress_bonds_stay_recorded() {
+    let mut contract = setup();
+    record_bond(&mut contract);
+
+    set_proposal_status("InProgress");
+    assert_eq!(contract.on_claim_bond(dao(), PROPOSAL_ID), Some(ProposalStatus::InProgress));
+
+    assert!(contract.view_bond(dao(), PROPOSAL_ID).is_some());
+    assert_eq!(contract.balances.get(&funder()), None);
+}
+
+// callback_proposal_status
+
+fn start_proposal(contract: &mut Contract) -> u64 {
+    let id = start_registration(contract, "member");
+    contract.internal_update_registration(id, RegistrationStatus::ProposalAdded, Some(PROPOSAL_ID), None);
+    record_bond(contract);
+    id
+}
+
+#[test]
+fn status_callback_keeps_in_progress_proposals_pending() {
+    let mut contract = setup();
+    let id = start_proposal(&mut contract);
+
+    set_proposal_status("InProgress");
+    contract.callback_proposal_status(id, dao(), PROPOSAL_ID);
+
+    assert!(contract.pending_registrations.contains(&id));
+    assert_eq!(registration(&contract, id).status, RegistrationStatus::AwaitingVotes);
+    assert!(contract.view_bond(dao(), PROPOSAL_ID).is_some());
+}
+
+#[test]
+fn status_callback_credits_approved_bond_once() {
+    let mut contract = setup();
+    let id = start_proposal(&mut contract);
+    contract.pending_registrations.insert(&id);
+
+    set_proposal_status("Approved");
+    contract.callback_proposal_status(id, dao(), PROPOSAL_ID);
+    set_proposal_status("Approved");
+    contract.callback_proposal_status(id, dao(), PROPOSAL_ID);
+
+    assert!(!contract.pending_registrations.contains(&id));
+    assert_eq!(registration(&contract, id).status, RegistrationStatus::ProposalApproved);
+    assert_eq!(contract.balances.get(&funder()), Some(BOND));
+}
+
+#[test]
+fn status_callback_fails_removed_proposals_without_credit() {
+    let mut contract = setup();
+    let id = start_proposal(&mut contract);
+
+    set_proposal_status("Removed");
+    contract.callback_proposal_status(id, dao(), PROPOSAL_ID);
+
+    assert_failed(&contract, id, "PROPOSAL REMOVED");
+    assert!(contract.view_bond(dao(), PROPOSAL_ID).is_none());
+    assert_eq!(contract.balances.get(&funder()), None);
+}
+
+#[test]
+fn failed_bot_vote_keeps_proposal_pending() {
+    let mut contract = setup();
+    let id = start_proposal(&mut contract);
+
+    set_promise_result(PromiseResult::Failed);
+    contract.callback_proposal_voted(id, dao(), PROPOSAL_ID);
+
+    assert!(contract.pending_registrations.contains(&id));
+    let registration = registration(&contract, id);
+    assert_eq!(registration.status, RegistrationStatus::AwaitingVotes);
+    assert_eq!(registration.proposal_id, Some(PROPOSAL_ID));
+    assert!(!event_names().contains(&"registration_failed".to_string()));
+}
+
+// dao configs
+
+fn roles_config(roles: &[&str]) -> DaoConfig {
+    DaoConfig {
+        authorization: FunderAuthorization::Roles(roles.iter().map(|role| role.to_string()).collect()),
+        human_requirement: None,
+        role_mappings: vec![],
+    }
+}
+
+/// Amount transferred to `receiver_id` by the receipts of the last call.
+fn transferred_to(receiver_id: &AccountId) -> Balance {
+    get_created_receipts().into_iter()
+        .filter(|receipt| receipt.receiver_id == *receiver_id)
+        .flat_map(|receipt| receipt.actions)
+        .map(|action| match action {
+            VmAction::Transfer { deposit } => deposit,
+            _ => 0,
+        })
+        .sum()
+}
+
+#[test]
+fn replaced_dao_config_storage_is_refunded_to_its_payer() {
+    let mut contract = setup();
+    testing_env!(context().predecessor_account_id(dao()).attached_deposit(ONE_NEAR).build());
+    contract.set_dao_config(dao(), roles_config(&["council", "onboarding", "community", "moderators"]));
+
+    testing_env!(context().predecessor_account_id(owner()).attached_deposit(ONE_NEAR).build());
+    contract.set_dao_config(dao(), roles_config(&["council"]));
+
+    assert!(transferred_to(&dao()) > 0);
+    assert!(transferred_to(&owner()) < ONE_NEAR);
+    assert_eq!(contract.dao_configs.get(&dao()).unwrap().payer, owner());
+}
+
+#[test]
+fn removed_dao_config_storage_is_refunded_to_its_payer() {
+    let mut contract = setup();
+    testing_env!(context().predecessor_account_id(owner()).attached_deposit(ONE_NEAR).build());
+    contract.set_dao_config(dao(), roles_config(&["onboarding"]));
+    let charged = ONE_NEAR - transferred_to(&owner());
+
+    testing_env!(context().predecessor_account_id(dao()).attached_deposit(1).build());
+    contract.remove_dao_config(dao());
+
+    assert_eq!(transferred_to(&owner()), charged);
+    assert_eq!(transferred_to(&dao()), 0);
+    assert!(matches!(contract.view_dao_config(dao()).authorization, FunderAuthorization::Roles(roles) if roles == vec![config().council_role]));
+}
+
+// drop configs
+
+#[test]
+fn drop_configs_are_kept_per_funder() {
+    let mut contract = setup();
+
+    // Someone else registering the same drop id can't take over the funder's config
+    let squatter: AccountId = "squatter.near".parse().unwrap();
+    testing_env!(context().predecessor_account_id(squatter.clone()).attached_deposit(ONE_NEAR).build());
+    let mut config = contract.view_drop_config(funder(), U128(DROP_ID)).unwrap().config;
+    config.dao_contract = "other.sputnik-dao.near".parse().unwrap();
+    contract.register_drop_config(U128(DROP_ID), config);
+
+    let config = contract.internal_use_drop(DROP_ID, &funder(), &dao(), &proposal("member").kind);
+    assert_eq!(config.dao_contract, dao());
+    assert_eq!(contract.view_drop_config(squatter, U128(DROP_ID)).unwrap().registrations, 0);
+}
+
+#[test]
+fn registration_storage_falls_back_to_funder_balance() {
+    let mut contract = setup();
+    contract.balances.insert(&funder(), &ONE_NEAR);
+
+    let (registration_id, left) = contract.internal_create_registration(&dao(), &funder(), U128(DROP_ID), &proposal("member").kind, 0);
+
+    assert_eq!(left, 0);
+    assert!(contract.balances.get(&funder()).unwrap() < ONE_NEAR);
+    assert_eq!(registration(&contract, registration_id).funder, funder());
+}
+
+#[test]
+fn migrate_carries_keypom_contract_over_from_v0_state() {
+    testing_env!(context().build());
+    let old_keypom: AccountId = "v1-3.keypom.near".parse().unwrap();
+    env::state_write(&old_keypom);
+    assert_eq!(Contract::internal_state_version(), StateVersion::V0);
+
+    let contract = Contract::migrate(Some(owner()), Some(config()));
+
+    assert_eq!(contract.config.keypom_contract, old_keypom);
+    assert_eq!(contract.config.sbt_registry, registry());
+    assert_eq!(contract.owner_id, owner());
+    assert_eq!(contract.view_state_version(), CURRENT_STATE_VERSION);
+}
+
+#[test]
+fn v1_flow_only_supports_membership_and_vote_proposals() {
+    let mut contract = setup();
+    assert!(v1_supports(&proposal("member").kind));
+    assert!(v1_supports(&ProposalKind::RemoveMemberFromRole { member_id: member(), role: "member".to_string() }));
+    assert!(v1_supports(&ProposalKind::Vote));
+    assert!(!v1_supports(&ProposalKind::Transfer { token_id: String::new(), receiver_id: member(), amount: U128(ONE_NEAR), msg: None }));
+    assert!(!v1_supports(&ProposalKind::ChangePolicyAddOrUpdateRole { role: group("admin", &[member()], &["*:*"]) }));
+
+    contract.config.flow = RegistrationFlow::V1;
+    assert_eq!(contract.view_capabilities().proposal_kinds, V1_PROPOSAL_KINDS.to_vec());
+}
+
+#[test]
+fn legacy_claims_are_credited_to_the_treasury() {
+    let mut contract = setup();
+    contract.config.accept_legacy_calls = true;
+    contract.balances.insert(&bot(), &ONE_NEAR);
+
+    // Arguments of a drop created against the v1 bot
+    let args: NewAutoRegistrationArgs = serde_json::from_value(json!({
+        "dao_contract": dao(),
+        "proposal": {"description": "mooooooooon", "kind": {"AddMemberToRole": {"role": "member", "member_id": member()}}},
+    })).unwrap();
+
+    testing_env!(context().predecessor_account_id(keypom()).attached_deposit(ONE_NEAR).build());
+    contract.new_auto_registration(args.dao_contract, args.keypom_args, args.funder, args.proposal, None, None);
+
+    let registration = registration(&contract, 0);
+    assert_eq!(registration.funder, bot());
+    assert_eq!(registration.member_id, Some(member()));
+    // Storage and bond came out of the attached deposit, never the treasury
+    assert_eq!(contract.balances.get(&bot()), Some(ONE_NEAR));
+}
+
+#[test]
+fn treasury_never_tops_up_deposits() {
+    let mut contract = setup();
+    contract.balances.insert(&bot(), &ONE_NEAR);
+
+    assert_eq!(contract.internal_rollover_deposit(&bot(), 0, V1_PROPOSAL_BOND), None);
+    assert_eq!(contract.balances.get(&bot()), Some(ONE_NEAR));
+}
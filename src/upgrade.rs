@@ -0,0 +1,89 @@
+use crate::*;
+use near_sdk::GasWeight;
+
+/// Storage key of the state version, kept outside of `STATE` so it can be read before
+/// knowing which layout `STATE` is in.
+const STATE_VERSION_KEY: &[u8] = b"STATE_VERSION";
+
+/// Layouts the contract state has been stored in. Add a variant, and its old layout struct,
+/// whenever a field of `Contract` changes.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateVersion {
+    /// Original release, only stored the Keypom contract.
+    V0,
+    /// Owner, admin roles, configs, registrations and pause flags.
+    V1,
+}
+
+pub const CURRENT_STATE_VERSION: StateVersion = StateVersion::V1;
+
+/// State of the original release, which predates state versioning.
+#[derive(BorshDeserialize)]
+struct ContractV0 {
+    keypom_contract: AccountId,
+}
+
+/// Keypom contract the original release defaulted to when it was never written to state.
+const V0_DEFAULT_KEYPOM_CONTRACT: &str = "v2.keypom.near";
+
+#[near_bindgen]
+impl Contract {
+    /// Deploy the wasm passed as raw input, i.e `near call <bot> upgrade --base64 <wasm>`,
+    /// and migrate the state with it. Only the owner can upgrade.
+    #[payable]
+    pub fn upgrade(&mut self) -> Promise {
+        assert_one_yocto();
+        self.assert_owner();
+        let code = env::input().unwrap_or_else(|| env::panic_str("NO CODE ATTACHED"));
+        log!("Upgrading from state version {:?}", CURRENT_STATE_VERSION);
+
+        // `migrate` gets whatever gas is left once the deploy, whose cost grows with the code size, is paid for
+        Promise::new(env::current_account_id())
+            .deploy_contract(code)
+            .function_call_weight("migrate".to_string(), b"{}".to_vec(), 0, Gas(0), GasWeight(1))
+    }
+
+    /// Bring the state stored by any previous version up to the current layout. Called by `upgrade`,
+    /// or by the bot account itself when deploying over the original release, which had no `upgrade`.
+    ///
+    /// The original release only stored the Keypom contract, so migrating from it takes `owner_id`,
+    /// the bot account by default, and `config`, the network default if omitted, whose `keypom_contract`
+    /// is replaced by the stored one.
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate(owner_id: Option<AccountId>, config: Option<ContractConfig>) -> Self {
+        let contract = match Self::internal_state_version() {
+            StateVersion::V0 => {
+                let keypom_contract = env::state_read::<ContractV0>()
+                    .map(|old| old.keypom_contract)
+                    .unwrap_or_else(|| V0_DEFAULT_KEYPOM_CONTRACT.parse().unwrap());
+                let config = config.or_else(ContractConfig::network_default)
+                    .unwrap_or_else(|| env::panic_str("CONFIG REQUIRED TO MIGRATE FROM V0"));
+                Self::new(
+                    owner_id.unwrap_or_else(env::current_account_id),
+                    Some(ContractConfig { keypom_contract, ..config }),
+                )
+            }
+            StateVersion::V1 => env::state_read::<Contract>().unwrap_or_else(|| env::panic_str("STATE NOT FOUND")),
+        };
+        Self::internal_write_state_version();
+        contract
+    }
+
+    pub fn view_state_version(&self) -> StateVersion {
+        Self::internal_state_version()
+    }
+}
+
+impl Contract {
+    /// Version of the stored state. Only the original release stored none.
+    pub(crate) fn internal_state_version() -> StateVersion {
+        env::storage_read(STATE_VERSION_KEY)
+            .map(|bytes| StateVersion::try_from_slice(&bytes).unwrap_or_else(|_| env::panic_str("INVALID STATE VERSION")))
+            .unwrap_or(StateVersion::V0)
+    }
+
+    pub(crate) fn internal_write_state_version() {
+        env::storage_write(STATE_VERSION_KEY, &CURRENT_STATE_VERSION.try_to_vec().unwrap());
+    }
+}
@@ -0,0 +1,49 @@
+use crate::*;
+
+/// Proposal bond attached by the v1 flow, which doesn't read the DAO policy. 0.1 $NEAR
+pub const V1_PROPOSAL_BOND: Balance = 100_000_000_000_000_000_000_000;
+
+/// Proposal kinds the v1 flow submits. It votes without any check, so it is limited to membership changes
+/// and signaling votes; everything else needs the checks of the v2 flow.
+pub const V1_PROPOSAL_KINDS: [&str; 3] = ["AddMemberToRole", "RemoveMemberFromRole", "Vote"];
+
+/// Whether the v1 flow may submit `kind`.
+pub fn v1_supports(kind: &ProposalKind) -> bool {
+    V1_PROPOSAL_KINDS.contains(&kind.name())
+}
+
+impl Contract {
+    /// Original flow: add the proposal right away and let `callback_new_auto_registration` vote on it.
+    /// No drop config, humanity or funder checks, the DAO trusts any claim relayed by Keypom.
+    pub(crate) fn internal_v1_registration(&mut self, dao_contract: AccountId, funder: AccountId, drop_id: U128, proposal: ProposalInput) -> Promise {
+        require!(!self.internal_is_paused(&dao_contract, drop_id.0), "REGISTRATIONS PAUSED");
+        require!(v1_supports(&proposal.kind), format!("V1 FLOW ONLY SUPPORTS {}", V1_PROPOSAL_KINDS.join(", ")));
+
+        // Ensure enough deposit is left after storage to add the proposal, rolling over the funder's
+        // credited balance if needed. Legacy claims must attach both in full
+        let (registration_id, attached) = self.internal_create_registration(&dao_contract, &funder, drop_id, &proposal.kind, env::attached_deposit());
+        let deposit = self.internal_rollover_deposit(&funder, attached, V1_PROPOSAL_BOND)
+            .unwrap_or_else(|| env::panic_str("ATTACH MORE NEAR, AT LEAST 0.1 $NEAR"));
+        DaoBotEvent::RegistrationStarted {
+            registration_id,
+            dao_contract: dao_contract.clone(),
+            funder: funder.clone(),
+            drop_id,
+            member_id: proposal.kind.claimer().cloned(),
+            proposal_kind: proposal.kind.name().to_string(),
+            human_only: false,
+        }.emit();
+        if deposit > V1_PROPOSAL_BOND {
+            self.internal_refund_deposit(&funder, deposit - V1_PROPOSAL_BOND);
+        }
+
+        ext_dao::ext(dao_contract.clone())
+        .with_attached_deposit(V1_PROPOSAL_BOND)
+        .with_static_gas(self.config.proposal_call_gas)
+        .add_proposal(proposal)
+        .then(
+            Self::ext(env::current_account_id())
+            .callback_new_auto_registration(registration_id, funder, dao_contract, U128(V1_PROPOSAL_BOND), U128(deposit - attached))
+        )
+    }
+}
@@ -3,17 +3,19 @@ use near_sdk::ext_contract;
 use crate::*;
 
 #[ext_contract(ext_dao)]
-trait ExtDao{
+pub trait ExtDao{
 
     fn get_policy(&self) -> Policy;
 
     fn act_proposal(&mut self, id: u64, action: Action, memo: Option<String>);
 
     fn add_proposal(&mut self, proposal: ProposalInput);
+
+    fn get_proposal(&self, id: u64) -> ProposalOutput;
 }
 
 #[ext_contract(ext_sbt_registry)]
-trait ExtSBTRegistry{
+pub trait ExtSBTRegistry{
 
     fn is_human(&self, account: AccountId) -> Vec<(AccountId, Vec<ClassId>)>;
 
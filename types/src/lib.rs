@@ -0,0 +1,47 @@
+//! Sputnik DAO v2 and SBT registry types shared by the bot and the mock contracts of its sandbox tests.
+//! They live outside of the bot crate so the mocks can use them without linking, and exporting,
+//! the bot's contract methods.
+
+pub mod ext_traits;
+mod policy;
+mod proposals;
+mod sbt;
+
+pub use policy::*;
+pub use proposals::*;
+pub use sbt::*;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{Base64VecU8, U128, U64};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{AccountId, Balance};
+use std::collections::{HashMap, HashSet};
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// Action to add proposal. Used internally.
+    AddProposal,
+    // Action to remove given proposal. Used for immediate deletion in special cases.
+    RemoveProposal,
+    /// Vote to approve given proposal or bounty.
+    VoteApprove,
+    /// Vote to reject given proposal or bounty.
+    VoteReject,
+    /// Vote to remove given proposal or bounty (because it's spam).
+    VoteRemove,
+    /// Finalize proposal, called when it's expired to return the funds
+    /// (or in the future can be used for early proposal closure).
+    Finalize,
+}
+
+impl Action {
+    pub fn to_policy_label(&self) -> &'static str {
+        match self {
+            Action::AddProposal => "AddProposal",
+            Action::RemoveProposal => "RemoveProposal",
+            Action::VoteApprove => "VoteApprove",
+            Action::VoteReject => "VoteReject",
+            Action::VoteRemove => "VoteRemove",
+            Action::Finalize => "Finalize",
+        }
+    }
+}
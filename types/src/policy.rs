@@ -0,0 +1,159 @@
+use crate::*;
+
+/// Policy of a sputnikdao2 DAO, as returned by `get_policy`.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Policy {
+    /// List of roles and permissions for them in the current policy.
+    pub roles: Vec<RolePermission>,
+    /// Default vote policy. Used when given proposal kind doesn't have special policy.
+    pub default_vote_policy: VotePolicy,
+    /// Proposal bond.
+    pub proposal_bond: U128,
+    /// Expiration period for proposals.
+    pub proposal_period: U64,
+    /// Bond for claiming a bounty.
+    pub bounty_bond: U128,
+    /// Period in which giving up on bounty is not punished.
+    pub bounty_forgiveness_period: U64,
+}
+
+impl Policy {
+    /// Role named `name`, if the policy has one.
+    pub fn role(&self, name: &str) -> Option<&RolePermission> {
+        self.roles.iter().find(|r| r.name == name)
+    }
+
+    /// Whether `account_id` may perform `action` on proposals with the policy label `kind_label`,
+    /// following sputnikdao2's `<proposal_kind>:<action>` permission matching.
+    /// Token weighted `Member` roles can't be evaluated by the bot and never match.
+    pub fn can_execute_action(&self, account_id: &AccountId, kind_label: &str, action: Action) -> bool {
+        self.roles.iter()
+            .filter(|role| match &role.kind {
+                RoleKind::Everyone => true,
+                RoleKind::Group(set) => set.contains(account_id),
+                RoleKind::Member(_) => false,
+            })
+            .any(|role| self.role_can_execute(role, kind_label, action))
+    }
+
+    /// `<proposal_kind>:<action>` permissions out of `actions` that `account_id` does not hold.
+    pub fn missing_permissions(&self, account_id: &AccountId, kind_label: &str, actions: &[Action]) -> Vec<String> {
+        actions.iter()
+            .filter(|action| !self.can_execute_action(account_id, kind_label, **action))
+            .map(|action| format!("{}:{}", kind_label, action.to_policy_label()))
+            .collect()
+    }
+
+    /// Whether `role` grants `action` on proposals with the policy label `kind_label`.
+    pub fn role_can_execute(&self, role: &RolePermission, kind_label: &str, action: Action) -> bool {
+        let action_label = action.to_policy_label();
+        role.permissions.contains(&format!("{}:{}", kind_label, action_label))
+            || role.permissions.contains(&format!("{}:*", kind_label))
+            || role.permissions.contains(&format!("*:{}", action_label))
+            || role.permissions.contains("*:*")
+    }
+
+    /// Vote policy `role` applies to proposals of kind `proposal_kind`.
+    pub fn vote_policy<'a>(&'a self, role: &'a RolePermission, proposal_kind: &ProposalKind) -> &'a VotePolicy {
+        role.vote_policy
+            .get(proposal_kind.to_policy_label())
+            .unwrap_or(&self.default_vote_policy)
+    }
+
+    /// Whether a single `VoteApprove` from `voter` on a fresh proposal of kind `proposal_kind`
+    /// reaches the threshold of one of its roles, following sputnikdao2's `proposal_status`.
+    /// Token weighted votes can't be predicted by the bot and are never considered decisive.
+    pub fn is_vote_decisive(&self, voter: &AccountId, proposal_kind: &ProposalKind) -> bool {
+        self.roles.iter()
+            .filter(|role| self.role_can_execute(role, proposal_kind.to_policy_label(), Action::VoteApprove))
+            .any(|role| {
+                let vote_policy = self.vote_policy(role, proposal_kind);
+                match (&role.kind, &vote_policy.weight_kind) {
+                    (RoleKind::Group(set), WeightKind::RoleWeight) if set.contains(voter) => {
+                        vote_policy.threshold_weight(set.len() as Balance) <= 1
+                    }
+                    _ => false,
+                }
+            })
+    }
+
+    /// Whether `account_id` is in the group of the role named `role`.
+    pub fn is_member_of(&self, account_id: &AccountId, role: &str) -> bool {
+        self.role(role)
+            .is_some_and(|r| matches!(&r.kind, RoleKind::Group(set) if set.contains(account_id)))
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RolePermission {
+    /// Name of the role to display to the user.
+    pub name: String,
+    /// Kind of the role: defines which users this permissions apply.
+    pub kind: RoleKind,
+    /// Set of actions on which proposals that this role is allowed to execute.
+    /// <proposal_kind>:<action>
+    pub permissions: HashSet<String>,
+    /// For each proposal kind, defines voting policy.
+    pub vote_policy: HashMap<String, VotePolicy>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum RoleKind {
+    /// Matches everyone, who is not matched by other roles.
+    Everyone,
+    /// Member greater or equal than given balance. Can use `1` as non-zero balance.
+    Member(U128),
+    /// Set of accounts.
+    Group(HashSet<AccountId>),
+}
+
+/// Defines configuration of the vote.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct VotePolicy {
+    /// Kind of weight to use for votes.
+    pub weight_kind: WeightKind,
+    /// Minimum number required for vote to finalize.
+    /// If weight kind is TokenWeight - this is minimum number of tokens required.
+    ///     This allows to avoid situation where the number of staked tokens from total supply is too small.
+    /// If RoleWeight - this is minimum number of votes.
+    ///     This allows to avoid situation where the role is got too small but policy kept at 1/2, for example.
+    pub quorum: U128,
+    /// How many votes to pass this vote.
+    pub threshold: WeightOrRatio,
+}
+
+impl VotePolicy {
+    /// Weight needed to pass given `total_weight`, the larger of the quorum and the threshold.
+    pub fn threshold_weight(&self, total_weight: Balance) -> Balance {
+        std::cmp::max(self.quorum.0, self.threshold.to_weight(total_weight))
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum WeightKind {
+    /// Using token amounts and total delegated at the moment.
+    TokenWeight,
+    /// Weight of the group role. Roles that don't have scoped group are not supported.
+    RoleWeight,
+}
+
+/// Direct weight or ratio to total weight, used for the voting policy.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum WeightOrRatio {
+    Weight(U128),
+    Ratio(u64, u64),
+}
+
+impl WeightOrRatio {
+    /// Convert weight or ratio to specific weight given total weight.
+    pub fn to_weight(&self, total_weight: Balance) -> Balance {
+        match self {
+            WeightOrRatio::Weight(weight) => std::cmp::min(weight.0, total_weight),
+            WeightOrRatio::Ratio(num, denom) => std::cmp::min(
+                (*num as Balance * total_weight) / *denom as Balance + 1,
+                total_weight,
+            ),
+        }
+    }
+}
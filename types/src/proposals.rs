@@ -0,0 +1,208 @@
+use crate::*;
+
+/// Proposal as accepted by sputnikdao2's `add_proposal`.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProposalInput {
+    /// Description of this proposal.
+    pub description: String,
+    /// Kind of proposal with relevant information.
+    pub kind: ProposalKind,
+}
+
+/// Kinds of proposals, mirroring the sputnikdao2 JSON shape.
+#[derive(Serialize, Deserialize, Debug)]
+pub enum ProposalKind {
+    /// Change the DAO config.
+    ChangeConfig { config: Config },
+    /// Add member to given role in the policy. This is short cut to updating the whole policy.
+    AddMemberToRole { member_id: AccountId, role: String },
+    /// Remove member to given role in the policy. This is short cut to updating the whole policy.
+    RemoveMemberFromRole { member_id: AccountId, role: String },
+    /// Calls `receiver_id` with list of method names in a single promise.
+    /// Allows this contract to execute any arbitrary set of actions in other contracts.
+    FunctionCall {
+        receiver_id: AccountId,
+        actions: Vec<ActionCall>,
+    },
+    /// Transfers given amount of `token_id` from this DAO to `receiver_id`.
+    /// If `msg` is not None, calls `ft_transfer_call` with given `msg`. Fails if this base token.
+    /// For `ft_transfer` and `ft_transfer_call` `memo` is the `description` of the proposal.
+    Transfer {
+        /// Can be "" for $NEAR or a valid account id.
+        token_id: String,
+        receiver_id: AccountId,
+        amount: U128,
+        msg: Option<String>,
+    },
+    /// Sets staking contract. Can only be proposed if staking contract is not set yet.
+    SetStakingContract { staking_id: AccountId },
+    /// Add new bounty.
+    AddBounty { bounty: Bounty },
+    /// Indicates that given bounty is done by given user.
+    BountyDone { bounty_id: u64, receiver_id: AccountId },
+    /// Just a signaling vote, with no execution.
+    Vote,
+    /// Add new role to the policy. If the role already exists, update it.
+    ChangePolicyAddOrUpdateRole { role: RolePermission },
+}
+
+/// Names of every `ProposalKind` the bot can submit, as returned by `ProposalKind::name`.
+pub const SUPPORTED_PROPOSAL_KINDS: [&str; 10] = [
+    "ChangeConfig",
+    "AddMemberToRole",
+    "RemoveMemberFromRole",
+    "FunctionCall",
+    "Transfer",
+    "SetStakingContract",
+    "AddBounty",
+    "BountyDone",
+    "Vote",
+    "ChangePolicyAddOrUpdateRole",
+];
+
+impl ProposalKind {
+    /// Name of the variant, as it appears in the proposal JSON.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ProposalKind::ChangeConfig { .. } => "ChangeConfig",
+            ProposalKind::AddMemberToRole { .. } => "AddMemberToRole",
+            ProposalKind::RemoveMemberFromRole { .. } => "RemoveMemberFromRole",
+            ProposalKind::FunctionCall { .. } => "FunctionCall",
+            ProposalKind::Transfer { .. } => "Transfer",
+            ProposalKind::SetStakingContract { .. } => "SetStakingContract",
+            ProposalKind::AddBounty { .. } => "AddBounty",
+            ProposalKind::BountyDone { .. } => "BountyDone",
+            ProposalKind::Vote => "Vote",
+            ProposalKind::ChangePolicyAddOrUpdateRole { .. } => "ChangePolicyAddOrUpdateRole",
+        }
+    }
+
+    /// Label used for this kind in sputnikdao2 policy permissions and vote policies.
+    pub fn to_policy_label(&self) -> &'static str {
+        match self {
+            ProposalKind::ChangeConfig { .. } => "config",
+            ProposalKind::AddMemberToRole { .. } => "add_member_to_role",
+            ProposalKind::RemoveMemberFromRole { .. } => "remove_member_from_role",
+            ProposalKind::FunctionCall { .. } => "call",
+            ProposalKind::Transfer { .. } => "transfer",
+            ProposalKind::SetStakingContract { .. } => "set_vote_token",
+            ProposalKind::AddBounty { .. } => "add_bounty",
+            ProposalKind::BountyDone { .. } => "bounty_done",
+            ProposalKind::Vote => "vote",
+            ProposalKind::ChangePolicyAddOrUpdateRole { .. } => "policy_add_or_update_role",
+        }
+    }
+
+    /// Field that Keypom is allowed to overwrite with the claiming account, if any.
+    /// Kinds without such a field must not receive an injected account at all.
+    pub fn claimer_field(&self) -> Option<&'static str> {
+        match self {
+            ProposalKind::AddMemberToRole { .. } | ProposalKind::RemoveMemberFromRole { .. } => Some("member_id"),
+            ProposalKind::Transfer { .. } | ProposalKind::BountyDone { .. } => Some("receiver_id"),
+            _ => None,
+        }
+    }
+
+    /// Full Keypom `account_id_field` path expected for this kind.
+    pub fn claimer_field_path(&self) -> Option<String> {
+        self.claimer_field()
+            .map(|field| format!("proposal.kind.{}.{}", self.name(), field))
+    }
+
+    /// Role targeted by this kind, if any.
+    pub fn role(&self) -> Option<&str> {
+        match self {
+            ProposalKind::AddMemberToRole { role, .. } | ProposalKind::RemoveMemberFromRole { role, .. } => Some(role),
+            _ => None,
+        }
+    }
+
+    /// Replace the role targeted by this kind, if it has one.
+    pub fn set_role(&mut self, new_role: String) {
+        if let ProposalKind::AddMemberToRole { role, .. } | ProposalKind::RemoveMemberFromRole { role, .. } = self {
+            *role = new_role;
+        }
+    }
+
+    /// Claiming account injected by Keypom, if this kind carries one.
+    pub fn claimer(&self) -> Option<&AccountId> {
+        match self {
+            ProposalKind::AddMemberToRole { member_id, .. } | ProposalKind::RemoveMemberFromRole { member_id, .. } => Some(member_id),
+            ProposalKind::Transfer { receiver_id, .. } | ProposalKind::BountyDone { receiver_id, .. } => Some(receiver_id),
+            _ => None,
+        }
+    }
+}
+
+/// DAO config, as used by `ProposalKind::ChangeConfig`.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Config {
+    /// Name of the DAO.
+    pub name: String,
+    /// Purpose of this DAO.
+    pub purpose: String,
+    /// Generic metadata. Can be used by specific UI to store additional data.
+    pub metadata: Base64VecU8,
+}
+
+/// Function call arguments.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ActionCall {
+    pub method_name: String,
+    pub args: Base64VecU8,
+    pub deposit: U128,
+    pub gas: U64,
+}
+
+/// Bounty information.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Bounty {
+    /// Description of the bounty.
+    pub description: String,
+    /// Token the bounty will be paid out.
+    /// Can be "" for $NEAR or a valid account id.
+    pub token: String,
+    /// Amount to be paid out.
+    pub amount: U128,
+    /// How many times this bounty can be done.
+    pub times: u32,
+    /// Max deadline from claim that can be spend on this bounty.
+    pub max_deadline: U64,
+}
+
+/// Status of a proposal on the DAO.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProposalStatus {
+    InProgress,
+    /// If quorum voted yes, this proposal is successfully approved.
+    Approved,
+    /// If quorum voted no, this proposal is rejected. Bond is returned.
+    Rejected,
+    /// If quorum voted to remove (e.g. spam), this proposal is rejected and bond is not returned.
+    /// Interfaces shouldn't show removed proposals.
+    Removed,
+    /// Expired after period of time.
+    Expired,
+    /// If proposal was moved to Hub or somewhere else.
+    Moved,
+    /// If proposal has failed when finalizing. Allowed to re-finalize again to either expire or approved.
+    Failed,
+}
+
+impl ProposalStatus {
+    /// Whether the DAO sent the proposal bond back to the proposer on reaching this status.
+    pub fn returns_bond(&self) -> bool {
+        matches!(self, ProposalStatus::Approved | ProposalStatus::Rejected | ProposalStatus::Expired)
+    }
+}
+
+/// Subset of sputnikdao2's `get_proposal` output used by the bot.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProposalOutput {
+    /// Id of the proposal.
+    pub id: u64,
+    /// Original proposer.
+    pub proposer: AccountId,
+    /// Current status of the proposal.
+    pub status: ProposalStatus,
+}